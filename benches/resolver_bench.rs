@@ -210,6 +210,39 @@ fn bench_concurrent_access(c: &mut Criterion) {
     });
 }
 
+fn bench_contended_cache_hits(c: &mut Criterion) {
+    const THREADS: usize = 8;
+    const LOOKUPS_PER_THREAD: usize = 1_000;
+
+    // Warm the cache from a snapshot so every lookup is a cache hit, not an override
+    let packages: Vec<String> = (0..64).map(|i| format!("@bench/cached{i}")).collect();
+    let snapshot = packages.iter().fold(MvrOverrides::new(), |snapshot, name| {
+        snapshot.with_package(name.clone(), "0x1".to_string())
+    });
+    let file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), snapshot.to_json().unwrap()).unwrap();
+    let resolver = MvrResolver::new(MvrConfig::testnet());
+    resolver.warm_cache_from_snapshot(file.path()).unwrap();
+
+    c.bench_function("contended_cache_hits", |b| {
+        b.iter(|| {
+            std::thread::scope(|scope| {
+                for thread in 0..THREADS {
+                    let (resolver, packages) = (&resolver, &packages);
+                    scope.spawn(move || {
+                        for i in 0..LOOKUPS_PER_THREAD {
+                            let name = &packages[(thread * 7 + i) % packages.len()];
+                            let result =
+                                futures::executor::block_on(resolver.resolve_package(name));
+                            black_box(result.unwrap());
+                        }
+                    });
+                }
+            });
+        });
+    });
+}
+
 fn bench_configuration_overhead(c: &mut Criterion) {
     let mut group = c.benchmark_group("configuration");
 
@@ -249,6 +282,7 @@ criterion_group!(
     bench_individual_vs_batch,
     bench_error_handling,
     bench_concurrent_access,
+    bench_contended_cache_hits,
     bench_configuration_overhead
 );
 criterion_main!(benches);
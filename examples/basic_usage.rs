@@ -51,7 +51,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("   Valid entries: {}", stats.valid_entries);
             println!("   Expired entries: {}", stats.expired_entries);
             println!("   Cache utilization: {:.1}%", stats.utilization() * 100.0);
-            println!("   Total hits: {}", stats.total_hits);
+            println!("   Hits: {}, misses: {}", stats.hits, stats.misses);
         }
         Err(e) => println!("✗ Failed to get cache stats: {e}"),
     }
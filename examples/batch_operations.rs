@@ -98,7 +98,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     match resolver.cache_stats() {
         Ok(stats) => {
             println!("   Total entries: {}", stats.total_entries);
-            println!("   Cache hits: {}", stats.total_hits);
+            println!("   Cache hits: {} ({} misses)", stats.hits, stats.misses);
             println!("   Hit rate: {:.1}%", stats.hit_rate() * 100.0);
             println!("   Utilization: {:.1}%", stats.utilization() * 100.0);
         }
@@ -0,0 +1,381 @@
+//! Normalized Move module interfaces of resolved packages
+//!
+//! These mirror the shapes returned by the Sui fullnode's
+//! `sui_getNormalizedMoveModulesByPackage` RPC, see
+//! [`crate::MvrResolver::get_package_modules`].
+
+use crate::error::{MvrError, MvrResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Public interface of a Move module
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveModule {
+    /// Address of the package defining the module
+    pub address: String,
+    /// Module name
+    pub name: String,
+    /// Structs declared in the module, by name
+    #[serde(default)]
+    pub structs: BTreeMap<String, MoveStruct>,
+    /// Functions callable from outside the module, by name
+    #[serde(default, rename = "exposedFunctions")]
+    pub functions: BTreeMap<String, MoveFunction>,
+}
+
+/// A struct declaration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveStruct {
+    /// Abilities of the struct
+    pub abilities: MoveAbilitySet,
+    /// Generic parameters of the struct
+    #[serde(default)]
+    pub type_parameters: Vec<MoveStructTypeParameter>,
+    /// Fields in declaration order
+    #[serde(default)]
+    pub fields: Vec<MoveField>,
+}
+
+/// A generic parameter of a struct
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveStructTypeParameter {
+    /// Abilities required of the type argument
+    pub constraints: MoveAbilitySet,
+    /// Whether the parameter is phantom
+    pub is_phantom: bool,
+}
+
+/// A struct field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveField {
+    /// Field name
+    pub name: String,
+    /// Field type
+    #[serde(rename = "type")]
+    pub type_: MoveType,
+}
+
+/// A function signature
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveFunction {
+    /// Who may call the function
+    pub visibility: MoveVisibility,
+    /// Whether the function can be called directly from a transaction
+    pub is_entry: bool,
+    /// Abilities required of each type argument
+    #[serde(default)]
+    pub type_parameters: Vec<MoveAbilitySet>,
+    /// Parameter types in order
+    #[serde(default)]
+    pub parameters: Vec<MoveType>,
+    /// Return types in order
+    #[serde(default, rename = "return")]
+    pub returns: Vec<MoveType>,
+}
+
+impl MoveFunction {
+    /// Parameters the caller supplies, without a trailing `TxContext`
+    pub fn explicit_parameters(&self) -> &[MoveType] {
+        match self.parameters.split_last() {
+            Some((last, rest)) if last.is_tx_context() => rest,
+            _ => &self.parameters,
+        }
+    }
+
+    /// Check call arguments against this signature before submitting a transaction
+    ///
+    /// Verifies the number of type arguments and arguments, and that each argument
+    /// is passed as a pure value or an object as its parameter type requires.
+    /// Generic parameters accept either kind.
+    pub fn check_arguments(
+        &self,
+        type_argument_count: usize,
+        arguments: &[CallArgKind],
+    ) -> MvrResult<()> {
+        if type_argument_count != self.type_parameters.len() {
+            return Err(MvrError::InvalidArguments(format!(
+                "expected {} type arguments, got {type_argument_count}",
+                self.type_parameters.len()
+            )));
+        }
+
+        let parameters = self.explicit_parameters();
+        if arguments.len() != parameters.len() {
+            return Err(MvrError::InvalidArguments(format!(
+                "expected {} arguments, got {}",
+                parameters.len(),
+                arguments.len()
+            )));
+        }
+
+        for (index, (parameter, argument)) in parameters.iter().zip(arguments).enumerate() {
+            match parameter.call_arg_kind() {
+                Some(expected) if expected != *argument => {
+                    return Err(MvrError::InvalidArguments(format!(
+                        "argument {index} of type {parameter} must be {expected}, got {argument}"
+                    )))
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How a call argument is passed to a Move function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CallArgKind {
+    /// BCS-encoded value
+    Pure,
+    /// Object reference
+    Object,
+}
+
+impl fmt::Display for CallArgKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CallArgKind::Pure => write!(f, "a pure value"),
+            CallArgKind::Object => write!(f, "an object"),
+        }
+    }
+}
+
+/// Function visibility
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MoveVisibility {
+    Public,
+    Friend,
+    Private,
+}
+
+/// Set of Move abilities
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveAbilitySet {
+    pub abilities: Vec<MoveAbility>,
+}
+
+/// A Move ability
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MoveAbility {
+    Copy,
+    Drop,
+    Store,
+    Key,
+}
+
+/// A Move type as it appears in signatures
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MoveType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    U256,
+    Address,
+    Signer,
+    /// A struct type, possibly generic
+    #[serde(rename_all = "camelCase")]
+    Struct {
+        address: String,
+        module: String,
+        name: String,
+        #[serde(default)]
+        type_arguments: Vec<MoveType>,
+    },
+    Vector(Box<MoveType>),
+    /// The function's type parameter at this index
+    TypeParameter(u16),
+    Reference(Box<MoveType>),
+    MutableReference(Box<MoveType>),
+}
+
+impl MoveType {
+    /// Whether this is `&TxContext` or `&mut TxContext`, supplied by the runtime
+    pub fn is_tx_context(&self) -> bool {
+        match self {
+            MoveType::Reference(inner) | MoveType::MutableReference(inner) => matches!(
+                inner.as_ref(),
+                MoveType::Struct { module, name, .. } if module == "tx_context" && name == "TxContext"
+            ),
+            _ => false,
+        }
+    }
+
+    /// How an argument for a parameter of this type is passed, `None` for generic
+    /// parameters that accept either
+    pub fn call_arg_kind(&self) -> Option<CallArgKind> {
+        match self {
+            MoveType::TypeParameter(_) => None,
+            MoveType::Reference(inner) | MoveType::MutableReference(inner) => inner.call_arg_kind(),
+            MoveType::Vector(inner) => inner.call_arg_kind(),
+            MoveType::Struct {
+                module,
+                name,
+                type_arguments,
+                ..
+            } => match (module.as_str(), name.as_str()) {
+                ("string", "String") | ("ascii", "String") | ("object", "ID") => {
+                    Some(CallArgKind::Pure)
+                }
+                ("option", "Option") => type_arguments.first().and_then(MoveType::call_arg_kind),
+                _ => Some(CallArgKind::Object),
+            },
+            _ => Some(CallArgKind::Pure),
+        }
+    }
+}
+
+impl fmt::Display for MoveType {
+    /// Move source syntax, with type parameters written as `T0`, `T1`, ...
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveType::Bool => write!(f, "bool"),
+            MoveType::U8 => write!(f, "u8"),
+            MoveType::U16 => write!(f, "u16"),
+            MoveType::U32 => write!(f, "u32"),
+            MoveType::U64 => write!(f, "u64"),
+            MoveType::U128 => write!(f, "u128"),
+            MoveType::U256 => write!(f, "u256"),
+            MoveType::Address => write!(f, "address"),
+            MoveType::Signer => write!(f, "signer"),
+            MoveType::Struct {
+                address,
+                module,
+                name,
+                type_arguments,
+            } => {
+                write!(f, "{address}::{module}::{name}")?;
+                if let Some((first, rest)) = type_arguments.split_first() {
+                    write!(f, "<{first}")?;
+                    for argument in rest {
+                        write!(f, ", {argument}")?;
+                    }
+                    write!(f, ">")?;
+                }
+                Ok(())
+            }
+            MoveType::Vector(inner) => write!(f, "vector<{inner}>"),
+            MoveType::TypeParameter(index) => write!(f, "T{index}"),
+            MoveType::Reference(inner) => write!(f, "&{inner}"),
+            MoveType::MutableReference(inner) => write!(f, "&mut {inner}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_normalized_module() {
+        let json = r#"{
+            "fileFormatVersion": 6,
+            "address": "0x2",
+            "name": "coin",
+            "friends": [],
+            "structs": {
+                "Coin": {
+                    "abilities": {"abilities": ["Store", "Key"]},
+                    "typeParameters": [{"constraints": {"abilities": []}, "isPhantom": true}],
+                    "fields": [{"name": "balance", "type": {"Struct": {
+                        "address": "0x2", "module": "balance", "name": "Balance",
+                        "typeArguments": [{"TypeParameter": 0}]
+                    }}}]
+                }
+            },
+            "exposedFunctions": {
+                "split": {
+                    "visibility": "Public",
+                    "isEntry": false,
+                    "typeParameters": [{"abilities": []}],
+                    "parameters": [
+                        {"MutableReference": {"Struct": {
+                            "address": "0x2", "module": "coin", "name": "Coin",
+                            "typeArguments": [{"TypeParameter": 0}]
+                        }}},
+                        "U64",
+                        {"MutableReference": {"Struct": {
+                            "address": "0x2", "module": "tx_context", "name": "TxContext",
+                            "typeArguments": []
+                        }}}
+                    ],
+                    "return": [{"Vector": "U8"}]
+                }
+            }
+        }"#;
+
+        let module: MoveModule = serde_json::from_str(json).unwrap();
+        assert!(module.structs["Coin"].type_parameters[0].is_phantom);
+        assert_eq!(
+            module.structs["Coin"].abilities.abilities,
+            [MoveAbility::Store, MoveAbility::Key]
+        );
+
+        let split = &module.functions["split"];
+        assert_eq!(split.visibility, MoveVisibility::Public);
+        let parameters: Vec<String> = split.parameters.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            parameters,
+            [
+                "&mut 0x2::coin::Coin<T0>",
+                "u64",
+                "&mut 0x2::tx_context::TxContext"
+            ]
+        );
+        assert_eq!(split.returns[0].to_string(), "vector<u8>");
+    }
+
+    #[test]
+    fn test_check_arguments() {
+        let coin = |inner: MoveType| MoveType::Struct {
+            address: "0x2".to_string(),
+            module: "coin".to_string(),
+            name: "Coin".to_string(),
+            type_arguments: vec![inner],
+        };
+        let tx_context = MoveType::MutableReference(Box::new(MoveType::Struct {
+            address: "0x2".to_string(),
+            module: "tx_context".to_string(),
+            name: "TxContext".to_string(),
+            type_arguments: vec![],
+        }));
+        let function = MoveFunction {
+            visibility: MoveVisibility::Public,
+            is_entry: true,
+            type_parameters: vec![MoveAbilitySet::default()],
+            parameters: vec![
+                MoveType::MutableReference(Box::new(coin(MoveType::TypeParameter(0)))),
+                MoveType::Vector(Box::new(MoveType::U64)),
+                MoveType::TypeParameter(0),
+                tx_context,
+            ],
+            returns: vec![],
+        };
+        assert_eq!(function.explicit_parameters().len(), 3);
+
+        use CallArgKind::{Object, Pure};
+        assert!(function.check_arguments(1, &[Object, Pure, Pure]).is_ok());
+        assert!(function.check_arguments(1, &[Object, Pure, Object]).is_ok());
+
+        for (type_arguments, arguments, message) in [
+            (0, &[Object, Pure, Pure][..], "expected 1 type arguments"),
+            (1, &[Object, Pure][..], "expected 3 arguments"),
+            (1, &[Pure, Pure, Pure][..], "argument 0"),
+            (1, &[Object, Object, Pure][..], "must be a pure value"),
+        ] {
+            let err = function
+                .check_arguments(type_arguments, arguments)
+                .unwrap_err();
+            assert!(matches!(err, MvrError::InvalidArguments(_)));
+            assert!(err.to_string().contains(message), "{err}");
+        }
+    }
+}
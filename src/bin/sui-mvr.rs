@@ -0,0 +1,137 @@
+//! Command-line interface for ad-hoc MVR resolution
+//!
+//! Run with: cargo run --features cli -- resolve @suifrens/core
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::json;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use sui_mvr::prelude::*;
+
+#[derive(Parser)]
+#[command(
+    name = "sui-mvr",
+    version,
+    about = "Resolve Move Registry names from the command line"
+)]
+struct Cli {
+    /// Network to resolve against
+    #[arg(long, value_enum, default_value_t = Network::Mainnet, global = true)]
+    network: Network,
+
+    /// Custom MVR endpoint URL (takes precedence over --network)
+    #[arg(long, global = true)]
+    endpoint: Option<String>,
+
+    /// JSON file with static overrides
+    #[arg(long, global = true)]
+    overrides: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Network {
+    Mainnet,
+    Testnet,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Resolve a package name to its address
+    Resolve {
+        /// Package name, e.g. @suifrens/core
+        name: String,
+    },
+    /// Resolve a type name to its full type signature
+    ResolveType {
+        /// Type name, e.g. @suifrens/core::suifren::SuiFren
+        type_name: String,
+    },
+    /// Resolve every name listed in a file (one per line, `#` starts a comment)
+    Batch {
+        /// File containing package and type names
+        file: PathBuf,
+    },
+    /// Check MVR names used in Rust sources for format errors and missing overrides
+    Lint {
+        /// Workspace directory or source file to scan
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli).await {
+        Ok(output) => {
+            println!("{output:#}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            let code = e.downcast_ref::<MvrError>().map(MvrError::code);
+            eprintln!("{}", json!({ "error": e.to_string(), "code": code }));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let mut config = match cli.network {
+        Network::Mainnet => MvrConfig::mainnet(),
+        Network::Testnet => MvrConfig::testnet(),
+    };
+    if let Some(endpoint) = cli.endpoint {
+        config = config.with_endpoint(endpoint);
+    }
+    if let Some(path) = cli.overrides {
+        config = config.with_overrides(MvrOverrides::from_json(&std::fs::read_to_string(path)?)?);
+    }
+
+    if let Command::Lint { path } = &cli.command {
+        let resolver = MvrResolver::new(config);
+        let report = sui_mvr::lint::lint_workspace(path, Some(&resolver))?;
+        if !report.is_clean() {
+            return Err(format!(
+                "{} issue(s) found: {}",
+                report.issues.len(),
+                serde_json::to_string(&report.issues)?
+            )
+            .into());
+        }
+        return Ok(serde_json::to_value(report)?);
+    }
+
+    let resolver = MvrResolver::try_new(config)?;
+
+    match cli.command {
+        Command::Resolve { name } => {
+            let address = resolver.resolve_package(&name).await?;
+            Ok(json!({ "name": name, "address": address }))
+        }
+        Command::ResolveType { type_name } => {
+            let type_signature = resolver.resolve_type(&type_name).await?;
+            Ok(json!({ "name": type_name, "type_signature": type_signature }))
+        }
+        Command::Batch { file } => {
+            let contents = std::fs::read_to_string(file)?;
+            let names: Vec<&str> = contents
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .collect();
+
+            // Anything with a module path is a type, everything else a package
+            let (types, packages): (Vec<&str>, Vec<&str>) =
+                names.into_iter().partition(|name| name.contains("::"));
+
+            let packages = resolver.resolve_packages(&packages).await?;
+            let types = resolver.resolve_types(&types).await?;
+            Ok(json!({ "packages": packages, "types": types }))
+        }
+        Command::Lint { .. } => unreachable!("lint runs without a resolver"),
+    }
+}
@@ -1,25 +1,40 @@
 use crate::error::{MvrError, MvrResult};
+use crate::Instant;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use tokio::time::{Duration, Instant};
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
 
 /// Cached resolution entry
 #[derive(Debug, Clone)]
 pub(crate) struct CacheEntry {
-    pub value: String,
+    /// Shared so that hits hand out a reference count instead of a copy
+    pub value: Arc<str>,
     pub expires_at: Instant,
+    /// Time between the last (re)validation and `expires_at`
+    pub lifetime: Duration,
     pub hit_count: u64,
     pub last_accessed: Instant,
+    /// Validator sent by the registry, used for conditional refreshes
+    pub etag: Option<String>,
 }
 
 impl CacheEntry {
-    pub fn new(value: String, ttl: Duration) -> Self {
+    /// Create an entry living for `ttl`, randomly stretched or shortened by up to
+    /// `jitter_percent` percent
+    pub fn new(value: Arc<str>, ttl: Duration, jitter_percent: u8) -> Self {
         let now = Instant::now();
+        let lifetime = jittered(ttl, jitter_percent);
         Self {
             value,
-            expires_at: now + ttl,
+            expires_at: now + lifetime,
+            lifetime,
             hit_count: 0,
             last_accessed: now,
+            etag: None,
         }
     }
 
@@ -27,135 +42,690 @@ impl CacheEntry {
         Instant::now() > self.expires_at
     }
 
-    pub fn access(&mut self) -> String {
+    pub fn access(&mut self) -> Arc<str> {
         self.hit_count += 1;
         self.last_accessed = Instant::now();
         self.value.clone()
     }
 }
 
+/// Marker for "no node" in the LRU list
+const NIL: usize = usize::MAX;
+
+#[derive(Debug)]
+struct LruNode {
+    key: String,
+    entry: CacheEntry,
+    prev: usize,
+    next: usize,
+}
+
+/// Map that keeps its entries in recency order with O(1) lookup, promotion and eviction
+///
+/// Nodes live in a slab and form a doubly linked list from most (`head`) to least
+/// (`tail`) recently used; the index maps keys to slab slots.
+#[derive(Debug)]
+struct LruMap {
+    index: HashMap<String, usize>,
+    nodes: Vec<Option<LruNode>>,
+    free: Vec<usize>,
+    head: usize,
+    tail: usize,
+    /// Estimated memory used by the entries, see [`entry_size`]
+    bytes: usize,
+    /// Estimated memory used by this map and the others sharing the counter
+    total_bytes: Arc<AtomicUsize>,
+}
+
+impl LruMap {
+    fn new(total_bytes: Arc<AtomicUsize>) -> Self {
+        Self {
+            index: HashMap::new(),
+            nodes: Vec::new(),
+            free: Vec::new(),
+            head: NIL,
+            tail: NIL,
+            bytes: 0,
+            total_bytes,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Look up an entry without changing its recency
+    fn get(&self, key: &str) -> Option<&CacheEntry> {
+        let slot = *self.index.get(key)?;
+        self.nodes[slot].as_ref().map(|node| &node.entry)
+    }
+
+    /// Look up an entry and mark it as most recently used
+    fn get_mut(&mut self, key: &str) -> Option<&mut CacheEntry> {
+        let slot = *self.index.get(key)?;
+        self.unlink(slot);
+        self.push_front(slot);
+        self.nodes[slot].as_mut().map(|node| &mut node.entry)
+    }
+
+    fn insert(&mut self, key: String, entry: CacheEntry) {
+        let size = entry_size(&key, &entry);
+        if let Some(existing) = self.get_mut(&key) {
+            let old_size = entry_size(&key, existing);
+            *existing = entry;
+            self.sub_bytes(old_size);
+            self.add_bytes(size);
+            return;
+        }
+        self.add_bytes(size);
+
+        let node = LruNode {
+            key: key.clone(),
+            entry,
+            prev: NIL,
+            next: NIL,
+        };
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.nodes[slot] = Some(node);
+                slot
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        };
+        self.push_front(slot);
+        self.index.insert(key, slot);
+    }
+
+    fn remove(&mut self, key: &str) -> Option<CacheEntry> {
+        let slot = self.index.remove(key)?;
+        self.take(slot).map(|node| node.entry)
+    }
+
+    /// Remove and return the least recently used entry
+    fn pop_lru(&mut self) -> Option<(String, CacheEntry)> {
+        if self.tail == NIL {
+            return None;
+        }
+        let node = self.take(self.tail)?;
+        self.index.remove(&node.key);
+        Some((node.key, node.entry))
+    }
+
+    /// Remove the entries `keep` rejects, returning their keys
+    fn retain(&mut self, mut keep: impl FnMut(&str, &CacheEntry) -> bool) -> Vec<String> {
+        let doomed: Vec<String> = self
+            .nodes
+            .iter()
+            .flatten()
+            .filter(|node| !keep(&node.key, &node.entry))
+            .map(|node| node.key.clone())
+            .collect();
+        for key in &doomed {
+            self.remove(key);
+        }
+        doomed
+    }
+
+    fn values(&self) -> impl Iterator<Item = &CacheEntry> {
+        self.nodes.iter().flatten().map(|node| &node.entry)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&str, &CacheEntry)> {
+        self.nodes
+            .iter()
+            .flatten()
+            .map(|node| (node.key.as_str(), &node.entry))
+    }
+
+    fn clear(&mut self) {
+        self.sub_bytes(self.bytes);
+        *self = Self::new(self.total_bytes.clone());
+    }
+
+    fn add_bytes(&mut self, size: usize) {
+        self.bytes += size;
+        self.total_bytes.fetch_add(size, Ordering::Relaxed);
+    }
+
+    fn sub_bytes(&mut self, size: usize) {
+        self.bytes -= size;
+        self.total_bytes.fetch_sub(size, Ordering::Relaxed);
+    }
+
+    fn take(&mut self, slot: usize) -> Option<LruNode> {
+        self.unlink(slot);
+        let node = self.nodes[slot].take();
+        self.free.push(slot);
+        if let Some(node) = &node {
+            self.sub_bytes(entry_size(&node.key, &node.entry));
+        }
+        node
+    }
+
+    fn unlink(&mut self, slot: usize) {
+        let Some(node) = self.nodes[slot].as_ref() else {
+            return;
+        };
+        let (prev, next) = (node.prev, node.next);
+
+        match prev {
+            NIL => self.head = next,
+            prev => self.node_mut(prev).next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.node_mut(next).prev = prev,
+        }
+
+        let node = self.node_mut(slot);
+        node.prev = NIL;
+        node.next = NIL;
+    }
+
+    fn push_front(&mut self, slot: usize) {
+        let old_head = self.head;
+        {
+            let node = self.node_mut(slot);
+            node.prev = NIL;
+            node.next = old_head;
+        }
+        match old_head {
+            NIL => self.tail = slot,
+            old_head => self.node_mut(old_head).prev = slot,
+        }
+        self.head = slot;
+    }
+
+    fn node_mut(&mut self, slot: usize) -> &mut LruNode {
+        self.nodes[slot]
+            .as_mut()
+            .expect("linked LRU slots are always occupied")
+    }
+}
+
+/// Estimated memory held by one entry: its strings plus bookkeeping
+///
+/// The key is stored twice, in the node and in the index.
+fn entry_size(key: &str, entry: &CacheEntry) -> usize {
+    2 * key.len()
+        + entry.value.len()
+        + entry.etag.as_ref().map_or(0, String::len)
+        + std::mem::size_of::<Option<LruNode>>()
+        + std::mem::size_of::<(String, usize)>()
+}
+
+/// Upper bound on the number of shards a cache is split into
+const MAX_SHARDS: usize = 16;
+
+/// Smallest shard worth splitting off; small caches keep a single, exact LRU order
+const MIN_SHARD_CAPACITY: usize = 64;
+
+/// One independently locked part of the cache
+#[derive(Debug)]
+struct Shard {
+    entries: Mutex<LruMap>,
+    capacity: usize,
+}
+
+impl Shard {
+    fn lock(&self) -> MvrResult<MutexGuard<'_, LruMap>> {
+        self.entries
+            .lock()
+            .map_err(|_| MvrError::CacheError("Failed to acquire cache lock".to_string()))
+    }
+}
+
+/// Receives cache events, e.g. to feed an application's metrics system
+///
+/// Keys are internal cache keys: `pkg:<name>` for packages and `type:<name>` for
+/// types. Callbacks run on the resolving task after the cache lock is released, so
+/// they should be cheap and must not block. Every method defaults to doing nothing.
+pub trait CacheObserver: Send + Sync {
+    /// A lookup was answered from the cache
+    fn on_hit(&self, _key: &str) {}
+
+    /// A lookup found no fresh entry
+    fn on_miss(&self, _key: &str) {}
+
+    /// An entry was dropped to make room for a new one
+    fn on_evict(&self, _key: &str) {}
+
+    /// An entry was found expired, by a lookup or by `cleanup_expired_cache`
+    fn on_expire(&self, _key: &str) {}
+}
+
 /// In-memory cache for MVR resolutions
-#[derive(Debug, Clone)]
+///
+/// Entries are spread over independently locked shards by key hash, so concurrent
+/// lookups of different names rarely wait on each other. Every hit promotes its entry,
+/// so shards use a `Mutex` rather than a read lock. Eviction is least-recently-used
+/// within a shard. The entry limit is split across shards, while the byte budget is
+/// shared: a shard that runs out of entries to evict makes room in the others.
+#[derive(Clone)]
 pub(crate) struct MvrCache {
-    entries: Arc<Mutex<HashMap<String, CacheEntry>>>,
+    shards: Arc<[Shard]>,
+    hasher: RandomState,
+    /// Lookups answered from the cache since it was created
+    hits: Arc<AtomicU64>,
+    /// Lookups that found no fresh entry since the cache was created
+    misses: Arc<AtomicU64>,
+    /// Entries dropped to make room since the cache was created
+    evictions: Arc<AtomicU64>,
+    created_at: Instant,
+    observer: Option<Arc<dyn CacheObserver>>,
+    /// TTL used by `insert`; the resolver always passes the per-kind TTL explicitly
+    #[cfg_attr(not(test), allow(dead_code))]
     default_ttl: Duration,
     max_size: usize,
+    max_bytes: Option<usize>,
+    /// Estimated memory used by the entries of all shards
+    bytes: Arc<AtomicUsize>,
+    /// Random TTL spread in percent, so entries warmed together don't expire together
+    ttl_jitter_percent: u8,
 }
 
 impl MvrCache {
+    #[cfg(test)]
     pub fn new(default_ttl: Duration, max_size: usize) -> Self {
+        Self::with_limits(default_ttl, max_size, None)
+    }
+
+    /// Cache holding at most `max_size` entries and, when set, about `max_bytes` bytes
+    pub fn with_limits(default_ttl: Duration, max_size: usize, max_bytes: Option<usize>) -> Self {
+        let count = (max_size / MIN_SHARD_CAPACITY)
+            .clamp(1, MAX_SHARDS)
+            .next_power_of_two();
+        // Spread the remainder so the shard limits add up to the cache limits
+        let split = |total: usize, i: usize| total / count + usize::from(i < total % count);
+        let bytes = Arc::new(AtomicUsize::new(0));
+        let shards = (0..count)
+            .map(|i| Shard {
+                entries: Mutex::new(LruMap::new(bytes.clone())),
+                capacity: split(max_size, i),
+            })
+            .collect();
+
         Self {
-            entries: Arc::new(Mutex::new(HashMap::new())),
+            shards,
+            hasher: RandomState::new(),
+            hits: Arc::new(AtomicU64::new(0)),
+            misses: Arc::new(AtomicU64::new(0)),
+            evictions: Arc::new(AtomicU64::new(0)),
+            created_at: Instant::now(),
+            observer: None,
             default_ttl,
             max_size,
+            max_bytes,
+            bytes,
+            ttl_jitter_percent: 0,
         }
     }
 
-    pub fn get(&self, key: &str) -> Option<String> {
-        let mut entries = self
-            .entries
-            .lock()
-            .map_err(|_| MvrError::CacheError("Failed to acquire cache lock".to_string()))
-            .ok()?;
-
-        if let Some(entry) = entries.get_mut(key) {
-            if !entry.is_expired() {
-                return Some(entry.access());
-            } else {
-                // Remove expired entry
-                entries.remove(key);
+    /// Spread entry lifetimes by up to ±`percent`% (capped at 100)
+    pub fn with_ttl_jitter(mut self, percent: u8) -> Self {
+        self.ttl_jitter_percent = percent.min(100);
+        self
+    }
+
+    /// Report cache events to `observer`
+    pub fn with_observer(mut self, observer: Arc<dyn CacheObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Fresh value under `key`, shared with the cache rather than copied
+    pub fn get(&self, key: &str) -> Option<Arc<str>> {
+        self.get_versioned(key).map(|(value, _)| value)
+    }
+
+    /// Fresh value under `key` together with the registry version (ETag) it came from
+    pub fn get_versioned(&self, key: &str) -> Option<(Arc<str>, Option<String>)> {
+        let (found, expired) = {
+            let mut entries = self.shard(key).lock().ok()?;
+            match entries.get_mut(key) {
+                Some(entry) if !entry.is_expired() => {
+                    (Some((entry.access(), entry.etag.clone())), false)
+                }
+                Some(entry) => {
+                    // Remove expired entry, unless it can still be revalidated with its ETag
+                    if entry.etag.is_none() {
+                        entries.remove(key);
+                    }
+                    (None, true)
+                }
+                None => (None, false),
             }
+        };
+
+        if expired {
+            #[cfg(feature = "tracing")]
+            tracing::trace!(key, "cache entry expired");
+            self.notify(|observer| observer.on_expire(key));
+        }
+
+        if found.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            #[cfg(feature = "tracing")]
+            tracing::trace!(key, "cache hit");
+            self.notify(|observer| observer.on_hit(key));
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            #[cfg(feature = "tracing")]
+            tracing::trace!(key, "cache miss");
+            self.notify(|observer| observer.on_miss(key));
         }
-        None
+        found
     }
 
-    pub fn insert(&self, key: String, value: String) -> MvrResult<()> {
+    #[cfg(test)]
+    pub fn insert(&self, key: String, value: impl Into<Arc<str>>) -> MvrResult<()> {
         self.insert_with_ttl(key, value, self.default_ttl)
     }
 
-    pub fn insert_with_ttl(&self, key: String, value: String, ttl: Duration) -> MvrResult<()> {
-        let mut entries = self
-            .entries
-            .lock()
-            .map_err(|_| MvrError::CacheError("Failed to acquire cache lock".to_string()))?;
+    pub fn insert_with_ttl(
+        &self,
+        key: String,
+        value: impl Into<Arc<str>>,
+        ttl: Duration,
+    ) -> MvrResult<()> {
+        self.insert_with_etag(key, value, ttl, None)
+    }
 
-        // Check if we need to evict entries
-        if entries.len() >= self.max_size {
-            self.evict_lru(&mut entries);
+    pub fn insert_with_etag(
+        &self,
+        key: String,
+        value: impl Into<Arc<str>>,
+        ttl: Duration,
+        etag: Option<String>,
+    ) -> MvrResult<()> {
+        let mut entry = CacheEntry::new(value.into(), ttl, self.ttl_jitter_percent);
+        entry.etag = etag;
+        self.insert_entry(key, entry, true).map(|_| ())
+    }
+
+    /// Insert unless a fresh entry is already cached under `key`
+    ///
+    /// Returns whether the value was inserted.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn insert_if_absent(
+        &self,
+        key: String,
+        value: impl Into<Arc<str>>,
+        ttl: Duration,
+    ) -> MvrResult<bool> {
+        let entry = CacheEntry::new(value.into(), ttl, self.ttl_jitter_percent);
+        self.insert_entry(key, entry, false)
+    }
+
+    fn insert_entry(&self, key: String, entry: CacheEntry, replace: bool) -> MvrResult<bool> {
+        let shard = self.shard(&key);
+        let size = entry_size(&key, &entry);
+        let max_bytes = self.max_bytes.unwrap_or(usize::MAX);
+
+        let mut entries = shard.lock()?;
+        if !replace && entries.get(&key).is_some_and(|entry| !entry.is_expired()) {
+            return Ok(false);
+        }
+        // The previous value under `key` no longer counts against the limits
+        entries.remove(&key);
+
+        // Entries that don't fit the byte budget on their own are not cached
+        if size > max_bytes {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(key = %key, size, "value too large to cache");
+            return Ok(false);
+        }
+
+        // Make room by count and by size
+        let mut evicted = Vec::new();
+        while entries.len() >= shard.capacity || self.bytes() + size > max_bytes {
+            match self.evict_lru(&mut entries) {
+                Some(key) => evicted.push(key),
+                None => break,
+            }
         }
 
-        let entry = CacheEntry::new(value, ttl);
+        #[cfg(feature = "tracing")]
+        tracing::trace!(key = %key, "cache insert");
+
         entries.insert(key, entry);
-        Ok(())
+        drop(entries);
+
+        // The shard had too little to evict, take the rest of the room from the others
+        for other in self.shards.iter() {
+            if self.bytes() <= max_bytes {
+                break;
+            }
+            if std::ptr::eq(other, shard) {
+                continue;
+            }
+            let mut entries = other.lock()?;
+            while self.bytes() > max_bytes {
+                match self.evict_lru(&mut entries) {
+                    Some(key) => evicted.push(key),
+                    None => break,
+                }
+            }
+        }
+
+        for key in &evicted {
+            self.notify(|observer| observer.on_evict(key));
+        }
+        Ok(true)
     }
 
-    #[allow(dead_code)]
-    pub fn remove(&self, key: &str) -> MvrResult<Option<String>> {
-        let mut entries = self
-            .entries
-            .lock()
-            .map_err(|_| MvrError::CacheError("Failed to acquire cache lock".to_string()))?;
+    /// ETag of the entry stored under `key`, whether or not it has expired
+    #[cfg_attr(not(feature = "network"), allow(dead_code))]
+    pub fn stale_etag(&self, key: &str) -> Option<String> {
+        let entries = self.shard(key).lock().ok()?;
+        entries.get(key).and_then(|entry| entry.etag.clone())
+    }
+
+    /// Mark the entry under `key` as fresh for another `ttl` and return its value
+    ///
+    /// Used when the registry confirms a stale entry with 304 Not Modified.
+    #[cfg_attr(not(feature = "network"), allow(dead_code))]
+    pub fn revalidate(&self, key: &str, ttl: Duration) -> MvrResult<Option<Arc<str>>> {
+        let mut entries = self.shard(key).lock()?;
+
+        Ok(entries.get_mut(key).map(|entry| {
+            entry.lifetime = jittered(ttl, self.ttl_jitter_percent);
+            entry.expires_at = Instant::now() + entry.lifetime;
+            entry.access()
+        }))
+    }
+
+    pub fn remove(&self, key: &str) -> MvrResult<Option<Arc<str>>> {
+        let mut entries = self.shard(key).lock()?;
 
         Ok(entries.remove(key).map(|entry| entry.value))
     }
 
+    /// Whether the fresh entry under `key` is within the last `percent`% of its lifetime
+    #[cfg_attr(
+        any(not(feature = "network"), target_arch = "wasm32"),
+        allow(dead_code)
+    )]
+    pub fn is_refresh_due(&self, key: &str, percent: u8) -> bool {
+        let Ok(entries) = self.shard(key).lock() else {
+            return false;
+        };
+        entries.get(key).is_some_and(|entry| {
+            let remaining = entry.expires_at.saturating_duration_since(Instant::now());
+            !entry.is_expired() && remaining <= entry.lifetime * u32::from(percent) / 100
+        })
+    }
+
+    /// Remove every entry resolved against registry version `version`
+    pub fn invalidate_version(&self, version: &str) -> MvrResult<usize> {
+        let mut removed = 0;
+        for shard in self.shards.iter() {
+            let mut entries = shard.lock()?;
+            removed += entries
+                .retain(|_, entry| entry.etag.as_deref() != Some(version))
+                .len();
+        }
+        Ok(removed)
+    }
+
+    /// Remove every package and type entry whose name starts with `prefix`
+    pub fn invalidate_prefix(&self, prefix: &str) -> MvrResult<usize> {
+        let mut removed = 0;
+        for shard in self.shards.iter() {
+            let mut entries = shard.lock()?;
+            removed += entries
+                .retain(|key, _| {
+                    let name = key
+                        .strip_prefix("pkg:")
+                        .or_else(|| key.strip_prefix("type:"))
+                        .or_else(|| key.strip_prefix("custom:"))
+                        .unwrap_or(key);
+                    !name.starts_with(prefix)
+                })
+                .len();
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(prefix, removed, "invalidated cache entries by prefix");
+        Ok(removed)
+    }
+
     pub fn clear(&self) -> MvrResult<()> {
-        let mut entries = self
-            .entries
-            .lock()
-            .map_err(|_| MvrError::CacheError("Failed to acquire cache lock".to_string()))?;
+        for shard in self.shards.iter() {
+            shard.lock()?.clear();
+        }
 
-        entries.clear();
+        #[cfg(feature = "tracing")]
+        tracing::debug!("cache cleared");
         Ok(())
     }
 
     pub fn stats(&self) -> MvrResult<CacheStats> {
-        let entries = self
-            .entries
-            .lock()
-            .map_err(|_| MvrError::CacheError("Failed to acquire cache lock".to_string()))?;
-
-        let total_entries = entries.len();
-        let expired_entries = entries
-            .iter()
-            .filter(|(_, entry)| entry.is_expired())
-            .count();
-
-        let total_hits: u64 = entries.values().map(|entry| entry.hit_count).sum();
+        let mut total_entries = 0;
+        let mut expired_entries = 0;
+        let mut total_hits = 0;
+        let mut estimated_bytes = 0;
+        for shard in self.shards.iter() {
+            let entries = shard.lock()?;
+            total_entries += entries.len();
+            estimated_bytes += entries.bytes();
+            expired_entries += entries.values().filter(|entry| entry.is_expired()).count();
+            total_hits += entries.values().map(|entry| entry.hit_count).sum::<u64>();
+        }
 
         Ok(CacheStats {
             total_entries,
             expired_entries,
             valid_entries: total_entries - expired_entries,
             total_hits,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
             max_size: self.max_size,
+            estimated_bytes,
+            max_bytes: self.max_bytes,
+            uptime: self.created_at.elapsed(),
         })
     }
 
     pub fn cleanup_expired(&self) -> MvrResult<usize> {
-        let mut entries = self
-            .entries
-            .lock()
-            .map_err(|_| MvrError::CacheError("Failed to acquire cache lock".to_string()))?;
+        let mut expired = Vec::new();
+        #[cfg(feature = "tracing")]
+        let mut remaining = 0;
+        for shard in self.shards.iter() {
+            let mut entries = shard.lock()?;
+            expired.extend(entries.retain(|_, entry| !entry.is_expired()));
+            #[cfg(feature = "tracing")]
+            {
+                remaining += entries.len();
+            }
+        }
 
-        let initial_size = entries.len();
-        entries.retain(|_, entry| !entry.is_expired());
-        Ok(initial_size - entries.len())
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            removed = expired.len(),
+            remaining,
+            "cleaned up expired cache entries"
+        );
+        for key in &expired {
+            self.notify(|observer| observer.on_expire(key));
+        }
+        Ok(expired.len())
     }
 
-    fn evict_lru(&self, entries: &mut HashMap<String, CacheEntry>) {
-        if entries.is_empty() {
-            return;
+    /// Keys of fresh entries that were read at least once and expire within `within`
+    ///
+    /// These are the candidates for refresh-ahead: refreshing them before they expire
+    /// keeps hot names from ever missing the cache.
+    #[cfg_attr(
+        any(not(feature = "network"), target_arch = "wasm32"),
+        allow(dead_code)
+    )]
+    pub fn expiring_keys(&self, within: Duration) -> MvrResult<Vec<String>> {
+        let now = Instant::now();
+        let horizon = now.checked_add(within);
+        let mut keys = Vec::new();
+        for shard in self.shards.iter() {
+            let entries = shard.lock()?;
+            keys.extend(
+                entries
+                    .iter()
+                    .filter(|(_, entry)| {
+                        entry.hit_count > 0
+                            && entry.expires_at > now
+                            && horizon.is_none_or(|horizon| entry.expires_at <= horizon)
+                    })
+                    .map(|(key, _)| key.to_string()),
+            );
         }
+        Ok(keys)
+    }
 
-        // Find the least recently used entry
-        let lru_key = entries
-            .iter()
-            .min_by_key(|(_, entry)| entry.last_accessed)
-            .map(|(key, _)| key.clone());
+    /// Keys and values of every unexpired entry, for persisting the cache
+    pub fn valid_entries(&self) -> MvrResult<Vec<(String, String)>> {
+        let mut valid = Vec::new();
+        for shard in self.shards.iter() {
+            let entries = shard.lock()?;
+            valid.extend(
+                entries
+                    .iter()
+                    .filter(|(_, entry)| !entry.is_expired())
+                    .map(|(key, entry)| (key.to_string(), entry.value.to_string())),
+            );
+        }
+        Ok(valid)
+    }
 
-        if let Some(key) = lru_key {
-            entries.remove(&key);
+    /// Shard responsible for `key`
+    fn shard(&self, key: &str) -> &Shard {
+        // The shard count is a power of two
+        &self.shards[self.hasher.hash_one(key) as usize & (self.shards.len() - 1)]
+    }
+
+    /// Drop the least recently used entry, returning its key
+    /// Estimated memory used by all entries
+    fn bytes(&self) -> usize {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    fn evict_lru(&self, entries: &mut LruMap) -> Option<String> {
+        let (key, _) = entries.pop_lru()?;
+        self.evictions.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(key = %key, "evicted least recently used cache entry");
+        Some(key)
+    }
+
+    fn notify(&self, event: impl FnOnce(&dyn CacheObserver)) {
+        if let Some(observer) = &self.observer {
+            event(observer.as_ref());
         }
     }
 
@@ -168,16 +738,46 @@ impl MvrCache {
     pub fn type_key(type_name: &str) -> String {
         format!("type:{type_name}")
     }
+
+    /// Create cache key for reverse resolution of a package address to its name
+    #[cfg(feature = "network")]
+    pub fn reverse_key(address: &str) -> String {
+        format!("rev:{address}")
+    }
+
+    /// Create cache key for SuiNS name resolution
+    #[cfg(feature = "network")]
+    pub fn name_key(name: &str) -> String {
+        format!("suins:{name}")
+    }
+
+    /// Create cache key for an application-defined value
+    pub fn custom_key(key: &str) -> String {
+        format!("custom:{key}")
+    }
 }
 
 /// Cache statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CacheStats {
     pub total_entries: usize,
     pub expired_entries: usize,
     pub valid_entries: usize,
+    /// Hits on the entries currently cached
     pub total_hits: u64,
+    /// Lookups answered from the cache since it was created
+    pub hits: u64,
+    /// Lookups that found no fresh entry since the cache was created
+    pub misses: u64,
+    /// Entries dropped to make room since the cache was created
+    pub evictions: u64,
     pub max_size: usize,
+    /// Estimated memory held by the cached entries
+    pub estimated_bytes: usize,
+    /// Budget for `estimated_bytes`, `None` when only the entry count is limited
+    pub max_bytes: Option<usize>,
+    /// Time since the cache was created
+    pub uptime: Duration,
 }
 
 impl CacheStats {
@@ -189,16 +789,89 @@ impl CacheStats {
         }
     }
 
+    /// Share of lookups answered from the cache, 0.0 before the first lookup
     pub fn hit_rate(&self) -> f64 {
-        if self.total_hits == 0 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+
+    /// Average evictions per minute since the cache was created
+    pub fn evictions_per_minute(&self) -> f64 {
+        let minutes = self.uptime.as_secs_f64() / 60.0;
+        if minutes == 0.0 {
             0.0
         } else {
-            // Fixed: Convert total_entries to u64 to match total_hits type
-            self.total_hits as f64 / (self.total_hits + self.total_entries as u64) as f64
+            self.evictions as f64 / minutes
         }
     }
 }
 
+/// [`CacheStats`] with its derived rates precomputed, for diagnostics endpoints
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheStatsReport {
+    pub total_entries: usize,
+    pub expired_entries: usize,
+    pub valid_entries: usize,
+    pub max_size: usize,
+    pub estimated_bytes: usize,
+    pub max_bytes: Option<usize>,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub uptime_secs: f64,
+    /// See [`CacheStats::hit_rate`]
+    pub hit_rate: f64,
+    /// See [`CacheStats::utilization`]
+    pub utilization: f64,
+    /// See [`CacheStats::evictions_per_minute`]
+    pub evictions_per_minute: f64,
+}
+
+impl From<&CacheStats> for CacheStatsReport {
+    fn from(stats: &CacheStats) -> Self {
+        Self {
+            total_entries: stats.total_entries,
+            expired_entries: stats.expired_entries,
+            valid_entries: stats.valid_entries,
+            max_size: stats.max_size,
+            estimated_bytes: stats.estimated_bytes,
+            max_bytes: stats.max_bytes,
+            hits: stats.hits,
+            misses: stats.misses,
+            evictions: stats.evictions,
+            uptime_secs: stats.uptime.as_secs_f64(),
+            hit_rate: stats.hit_rate(),
+            utilization: stats.utilization(),
+            evictions_per_minute: stats.evictions_per_minute(),
+        }
+    }
+}
+
+impl From<CacheStats> for CacheStatsReport {
+    fn from(stats: CacheStats) -> Self {
+        Self::from(&stats)
+    }
+}
+
+/// Scale `ttl` by a random factor in `[1 - percent/100, 1 + percent/100)`
+fn jittered(ttl: Duration, percent: u8) -> Duration {
+    if percent == 0 {
+        return ttl;
+    }
+
+    // Each `RandomState` is freshly keyed, which is random enough to spread expiry
+    // without pulling in an RNG dependency
+    let bits = RandomState::new().build_hasher().finish();
+    let unit = (bits >> 11) as f64 / (1u64 << 53) as f64;
+    let factor = 1.0 + (unit * 2.0 - 1.0) * f64::from(percent.min(100)) / 100.0;
+
+    Duration::try_from_secs_f64(ttl.as_secs_f64() * factor).unwrap_or(ttl)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -212,14 +885,19 @@ mod tests {
         cache
             .insert("key1".to_string(), "value1".to_string())
             .unwrap();
-        assert_eq!(cache.get("key1"), Some("value1".to_string()));
+        assert_eq!(cache.get("key1").as_deref(), Some("value1"));
+        // Hits share the stored value instead of copying it
+        assert!(Arc::ptr_eq(
+            &cache.get("key1").unwrap(),
+            &cache.get("key1").unwrap()
+        ));
 
         // Test non-existent key
         assert_eq!(cache.get("nonexistent"), None);
 
         // Test removal
         let removed = cache.remove("key1").unwrap();
-        assert_eq!(removed, Some("value1".to_string()));
+        assert_eq!(removed.as_deref(), Some("value1"));
         assert_eq!(cache.get("key1"), None);
     }
 
@@ -230,7 +908,7 @@ mod tests {
         cache
             .insert("key1".to_string(), "value1".to_string())
             .unwrap();
-        assert_eq!(cache.get("key1"), Some("value1".to_string()));
+        assert_eq!(cache.get("key1").as_deref(), Some("value1"));
 
         // Wait for expiration
         sleep(Duration::from_millis(150)).await;
@@ -257,9 +935,9 @@ mod tests {
             .insert("key3".to_string(), "value3".to_string())
             .unwrap();
 
-        assert_eq!(cache.get("key1"), Some("value1".to_string()));
+        assert_eq!(cache.get("key1").as_deref(), Some("value1"));
         assert_eq!(cache.get("key2"), None);
-        assert_eq!(cache.get("key3"), Some("value3".to_string()));
+        assert_eq!(cache.get("key3").as_deref(), Some("value3"));
     }
 
     #[test]
@@ -276,11 +954,26 @@ mod tests {
         // Access key1 multiple times
         cache.get("key1");
         cache.get("key1");
+        cache.get("missing");
 
         let stats = cache.stats().unwrap();
         assert_eq!(stats.total_entries, 2);
         assert_eq!(stats.valid_entries, 2);
         assert!(stats.total_hits >= 2);
+        assert_eq!((stats.hits, stats.misses), (2, 1));
+        assert!((stats.hit_rate() - 2.0 / 3.0).abs() < f64::EPSILON);
+
+        // Counters are shared with clones and survive clearing
+        cache.clone().get("key2");
+        cache.clear().unwrap();
+        assert_eq!(cache.stats().unwrap().hits, 3);
+        assert_eq!(
+            MvrCache::new(Duration::from_secs(1), 10)
+                .stats()
+                .unwrap()
+                .hit_rate(),
+            0.0
+        );
     }
 
     #[test]
@@ -314,6 +1007,346 @@ mod tests {
         assert_eq!(stats.total_entries, 0);
     }
 
+    #[tokio::test]
+    async fn test_cache_etag_revalidation() {
+        let cache = MvrCache::new(Duration::from_secs(10), 10);
+
+        cache
+            .insert_with_etag(
+                "key1".to_string(),
+                "value1".to_string(),
+                Duration::ZERO,
+                Some("\"v1\"".to_string()),
+            )
+            .unwrap();
+        cache
+            .insert_with_ttl("key2".to_string(), "value2".to_string(), Duration::ZERO)
+            .unwrap();
+        sleep(Duration::from_millis(5)).await;
+
+        // Expired entries are misses, but ETag-carrying ones are kept for revalidation
+        assert_eq!(cache.get("key1"), None);
+        assert_eq!(cache.get("key2"), None);
+        assert_eq!(cache.stale_etag("key1"), Some("\"v1\"".to_string()));
+        assert_eq!(cache.stale_etag("key2"), None);
+
+        let value = cache.revalidate("key1", Duration::from_secs(60)).unwrap();
+        assert_eq!(value.as_deref(), Some("value1"));
+        assert_eq!(cache.get("key1").as_deref(), Some("value1"));
+        assert_eq!(
+            cache.revalidate("key2", Duration::from_secs(60)).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cache_invalidate_version() {
+        let cache = MvrCache::new(Duration::from_secs(10), 10);
+        let ttl = Duration::from_secs(10);
+
+        for (key, version) in [("key1", "\"v1\""), ("key2", "\"v1\""), ("key3", "\"v2\"")] {
+            cache
+                .insert_with_etag(
+                    key.to_string(),
+                    "value".to_string(),
+                    ttl,
+                    Some(version.to_string()),
+                )
+                .unwrap();
+        }
+
+        assert_eq!(
+            cache.get_versioned("key3"),
+            Some(("value".into(), Some("\"v2\"".to_string())))
+        );
+        assert_eq!(cache.invalidate_version("\"v1\"").unwrap(), 2);
+        assert_eq!(cache.get("key1"), None);
+        assert_eq!(cache.get("key3").as_deref(), Some("value"));
+    }
+
+    #[test]
+    fn test_cache_invalidate_prefix() {
+        let cache = MvrCache::new(Duration::from_secs(10), 10);
+        for key in [
+            MvrCache::package_key("@suifrens/core"),
+            MvrCache::package_key("@suifrens/accessories"),
+            MvrCache::type_key("@suifrens/core::suifren::SuiFren"),
+            MvrCache::package_key("@suifrens-fans/club"),
+            MvrCache::package_key("@other/pkg"),
+        ] {
+            cache.insert(key, "value".to_string()).unwrap();
+        }
+
+        assert_eq!(cache.invalidate_prefix("@suifrens/").unwrap(), 3);
+        assert_eq!(cache.get(&MvrCache::package_key("@suifrens/core")), None);
+        assert!(cache
+            .get(&MvrCache::package_key("@suifrens-fans/club"))
+            .is_some());
+        assert!(cache.get(&MvrCache::package_key("@other/pkg")).is_some());
+        assert_eq!(cache.invalidate_prefix("@missing/").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_ttl_jitter() {
+        let ttl = Duration::from_secs(100);
+        assert_eq!(jittered(ttl, 0), ttl);
+
+        let samples: Vec<Duration> = (0..50).map(|_| jittered(ttl, 20)).collect();
+        assert!(samples
+            .iter()
+            .all(|s| *s >= Duration::from_secs(80) && *s < Duration::from_secs(120)));
+        assert!(samples.iter().any(|s| *s != samples[0]));
+
+        // Stretching a huge TTL falls back to the unjittered value instead of panicking
+        for _ in 0..20 {
+            assert!(jittered(Duration::MAX, 100) <= Duration::MAX);
+        }
+    }
+
+    #[test]
+    fn test_lru_map_order() {
+        let ttl = Duration::from_secs(10);
+        let mut map = LruMap::new(Arc::default());
+        for key in ["a", "b", "c"] {
+            map.insert(key.to_string(), CacheEntry::new(key.into(), ttl, 0));
+        }
+
+        // Touching "a" makes "b" the oldest; replacing "b" refreshes it too
+        map.get_mut("a");
+        map.insert("b".to_string(), CacheEntry::new("b2".into(), ttl, 0));
+        assert_eq!(map.pop_lru().map(|(key, _)| key).as_deref(), Some("c"));
+
+        // Freed slots are reused
+        map.remove("a");
+        map.insert("d".to_string(), CacheEntry::new("d".into(), ttl, 0));
+        assert_eq!(map.nodes.len(), 3);
+        assert_eq!(map.get("b").map(|e| &*e.value), Some("b2"));
+        assert_eq!(map.pop_lru().map(|(key, _)| key).as_deref(), Some("b"));
+        assert_eq!(map.pop_lru().map(|(key, _)| key).as_deref(), Some("d"));
+        assert!(map.pop_lru().is_none());
+        assert_eq!(map.len(), 0);
+    }
+
+    #[test]
+    fn test_cache_eviction_at_scale() {
+        let cache = MvrCache::new(Duration::from_secs(60), 100_000);
+        for i in 0..150_000 {
+            cache.insert(format!("key{i}"), i.to_string()).unwrap();
+        }
+
+        // Each shard evicts its own least recently used entries
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.total_entries, 100_000);
+        assert_eq!(cache.get("key0"), None);
+        assert_eq!(cache.get("key149999").as_deref(), Some("149999"));
+    }
+
+    #[test]
+    fn test_insert_if_absent() {
+        let cache = MvrCache::new(Duration::from_secs(60), 10);
+        assert!(cache
+            .insert_if_absent("a".to_string(), "1".to_string(), Duration::ZERO)
+            .unwrap());
+        // Expired entries may be replaced, fresh ones are kept
+        assert!(cache
+            .insert_if_absent("a".to_string(), "2".to_string(), Duration::from_secs(60))
+            .unwrap());
+        assert!(!cache
+            .insert_if_absent("a".to_string(), "3".to_string(), Duration::from_secs(60))
+            .unwrap());
+        assert_eq!(cache.get("a").as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn test_expiring_keys() {
+        let cache = MvrCache::new(Duration::from_secs(60), 10);
+        cache
+            .insert_with_ttl("soon".to_string(), "1".to_string(), Duration::from_secs(5))
+            .unwrap();
+        cache
+            .insert_with_ttl(
+                "unread".to_string(),
+                "2".to_string(),
+                Duration::from_secs(5),
+            )
+            .unwrap();
+        cache.insert("later".to_string(), "3".to_string()).unwrap();
+        cache.get("soon");
+        cache.get("later");
+
+        assert_eq!(
+            cache.expiring_keys(Duration::from_secs(10)).unwrap(),
+            ["soon"]
+        );
+        assert_eq!(
+            cache.expiring_keys(Duration::from_secs(1)).unwrap(),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_cache_byte_budget() {
+        let ttl = Duration::from_secs(60);
+        let size = entry_size("key0", &CacheEntry::new("x".repeat(100).into(), ttl, 0));
+        let cache = MvrCache::with_limits(ttl, 100, Some(3 * size));
+
+        for i in 0..5 {
+            cache.insert(format!("key{i}"), "x".repeat(100)).unwrap();
+        }
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.total_entries, 3);
+        assert_eq!(stats.estimated_bytes, 3 * size);
+        assert_eq!(stats.evictions, 2);
+        assert_eq!(cache.get("key1"), None);
+        assert!(cache.get("key4").is_some());
+
+        // One large value displaces several small ones, replacing a key frees its old size
+        cache.insert("key4".to_string(), "x".repeat(20)).unwrap();
+        cache.insert("big".to_string(), "x".repeat(150)).unwrap();
+        assert!(cache.get("big").is_some());
+        assert!(cache.stats().unwrap().estimated_bytes <= 3 * size);
+
+        // Values larger than the whole budget are not cached at all
+        cache
+            .insert("huge".to_string(), "x".repeat(10_000))
+            .unwrap();
+        assert_eq!(cache.get("huge"), None);
+        assert!(cache.get("big").is_some());
+
+        cache.clear().unwrap();
+        assert_eq!(cache.stats().unwrap().estimated_bytes, 0);
+    }
+
+    #[test]
+    fn test_cache_byte_budget_across_shards() {
+        let ttl = Duration::from_secs(60);
+        let max_bytes = 64 * 1024;
+        let cache = MvrCache::with_limits(ttl, 1024, Some(max_bytes));
+        assert_eq!(cache.shards.len(), MAX_SHARDS);
+
+        for i in 0..500 {
+            cache.insert(format!("key{i}"), "x".repeat(100)).unwrap();
+        }
+        assert!(cache.stats().unwrap().estimated_bytes <= max_bytes);
+
+        // A value over one shard's share of the budget is still cached, evicting
+        // from other shards once its own has nothing left
+        let big = "x".repeat(max_bytes / 2);
+        cache.insert("big".to_string(), big.clone()).unwrap();
+        assert_eq!(cache.get("big").as_deref(), Some(big.as_str()));
+        let stats = cache.stats().unwrap();
+        assert!(stats.estimated_bytes <= max_bytes);
+        assert_eq!(stats.estimated_bytes, cache.bytes());
+        assert!(stats.total_entries > 1);
+
+        cache.clear().unwrap();
+        assert_eq!(cache.bytes(), 0);
+    }
+
+    #[test]
+    fn test_cache_shards() {
+        // Small caches keep one exact LRU order
+        assert_eq!(MvrCache::new(Duration::from_secs(60), 2).shards.len(), 1);
+
+        let cache = MvrCache::new(Duration::from_secs(60), 1000);
+        assert_eq!(cache.shards.len(), MAX_SHARDS);
+        let capacity: usize = cache.shards.iter().map(|shard| shard.capacity).sum();
+        assert_eq!(capacity, 1000);
+
+        std::thread::scope(|scope| {
+            for thread in 0..8 {
+                let cache = &cache;
+                scope.spawn(move || {
+                    for i in 0..50 {
+                        let key = format!("key{thread}-{i}");
+                        cache.insert(key.clone(), i.to_string()).unwrap();
+                        assert_eq!(cache.get(&key).as_deref(), Some(i.to_string().as_str()));
+                    }
+                });
+            }
+        });
+        assert_eq!(cache.stats().unwrap().total_entries, 400);
+    }
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl CacheObserver for RecordingObserver {
+        fn on_hit(&self, key: &str) {
+            self.events.lock().unwrap().push(format!("hit {key}"));
+        }
+
+        fn on_miss(&self, key: &str) {
+            self.events.lock().unwrap().push(format!("miss {key}"));
+        }
+
+        fn on_evict(&self, key: &str) {
+            self.events.lock().unwrap().push(format!("evict {key}"));
+        }
+
+        fn on_expire(&self, key: &str) {
+            self.events.lock().unwrap().push(format!("expire {key}"));
+        }
+    }
+
+    #[test]
+    fn test_cache_observer() {
+        let observer = Arc::new(RecordingObserver::default());
+        let cache = MvrCache::new(Duration::from_secs(10), 1).with_observer(observer.clone());
+
+        cache.insert("a".to_string(), "1".to_string()).unwrap();
+        cache.get("a");
+        cache.get("b");
+        cache.insert("b".to_string(), "2".to_string()).unwrap();
+        cache
+            .insert_with_ttl("b".to_string(), "2".to_string(), Duration::ZERO)
+            .unwrap();
+        assert_eq!(cache.cleanup_expired().unwrap(), 1);
+
+        assert_eq!(
+            *observer.events.lock().unwrap(),
+            ["hit a", "miss b", "evict a", "expire b"]
+        );
+    }
+
+    #[test]
+    fn test_cache_stats_report() {
+        let cache = MvrCache::new(Duration::from_secs(10), 2);
+        for key in ["a", "b", "c"] {
+            cache.insert(key.to_string(), key.to_string()).unwrap();
+        }
+        cache.get("c");
+        cache.get("a");
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.evictions, 1);
+
+        let stats = CacheStats {
+            uptime: Duration::from_secs(30),
+            ..stats
+        };
+        assert_eq!(stats.evictions_per_minute(), 2.0);
+
+        let report = CacheStatsReport::from(&stats);
+        assert_eq!(report.hit_rate, 0.5);
+        assert_eq!(report.utilization, 1.0);
+        assert_eq!(report.evictions_per_minute, 2.0);
+        assert_eq!(report.uptime_secs, 30.0);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["hits"], 1);
+        assert_eq!(json["evictions_per_minute"], 2.0);
+        assert_eq!(
+            serde_json::from_value::<CacheStatsReport>(json).unwrap(),
+            report
+        );
+        assert!(serde_json::to_string(&stats)
+            .unwrap()
+            .contains("\"evictions\":1"));
+    }
+
     #[test]
     fn test_cache_clone() {
         let cache = MvrCache::new(Duration::from_secs(1), 10);
@@ -325,6 +1358,6 @@ mod tests {
             .unwrap();
 
         // Should be accessible from clone (shared Arc)
-        assert_eq!(cloned_cache.get("key1"), Some("value1".to_string()));
+        assert_eq!(cloned_cache.get("key1").as_deref(), Some("value1"));
     }
 }
@@ -1,7 +1,11 @@
+use std::borrow::Cow;
+use std::fmt;
+
 /// Error types for MVR operations
 #[derive(Debug, thiserror::Error)]
 pub enum MvrError {
     /// HTTP request failed
+    #[cfg(feature = "network")]
     #[error("HTTP request failed: {0}")]
     HttpError(#[from] reqwest::Error),
 
@@ -10,8 +14,12 @@ pub enum MvrError {
     JsonError(#[from] serde_json::Error),
 
     /// Package not found in MVR
-    #[error("Package '{0}' not found in MVR")]
-    PackageNotFound(String),
+    #[error("Package '{name}' not found in MVR{}", did_you_mean(.suggestions))]
+    PackageNotFound {
+        name: String,
+        /// Similar registered names, closest first, see [`crate::MvrConfig::with_name_suggestions`]
+        suggestions: Vec<String>,
+    },
 
     /// Type not found in MVR
     #[error("Type '{0}' not found in MVR")]
@@ -22,24 +30,56 @@ pub enum MvrError {
     CacheError(String),
 
     /// Invalid package name format
-    #[error("Invalid package name format: '{0}'. Expected format: @namespace/package")]
+    #[error(
+        "Invalid package name format: '{0}'{}. Expected format: @namespace/package",
+        reason_suffix(check_package_name(.0))
+    )]
     InvalidPackageName(String),
 
     /// Invalid type name format
-    #[error("Invalid type name format: '{0}'. Expected format: @namespace/package::module::Type")]
+    #[error(
+        "Invalid type name format: '{0}'{}. Expected format: @namespace/package::module::Type",
+        reason_suffix(check_type_name(.0))
+    )]
     InvalidTypeName(String),
 
+    /// Malformed Move call target, with what is wrong with it
+    #[error(
+        "Invalid Move call target: '{target}': {reason}. Expected format: @namespace/package::module::function"
+    )]
+    InvalidTarget { target: String, reason: String },
+
     /// Network timeout
     #[error("Request timed out after {timeout_secs} seconds")]
     Timeout { timeout_secs: u64 },
 
     /// Rate limit exceeded
-    #[error("Rate limit exceeded. Try again in {retry_after_secs} seconds")]
-    RateLimitExceeded { retry_after_secs: u64 },
+    #[error(
+        "Rate limit exceeded. Try again in {retry_after_secs} seconds{}",
+        request_suffix(.context, .request_id)
+    )]
+    RateLimitExceeded {
+        retry_after_secs: u64,
+        /// `X-Request-Id` of the rejected request, when it was sent by the resolver
+        request_id: Option<String>,
+        /// Request that was rejected, when it was sent by the resolver
+        context: Option<Box<RequestContext>>,
+    },
 
     /// Server error
-    #[error("Server error: {status_code} - {message}")]
-    ServerError { status_code: u16, message: String },
+    #[error(
+        "Server error: {status_code} - {message}{}",
+        request_suffix(.context, .request_id)
+    )]
+    ServerError {
+        status_code: u16,
+        /// Response body, truncated to [`MAX_ERROR_BODY_LEN`] bytes
+        message: String,
+        /// `X-Request-Id` of the failed request, when it was sent by the resolver
+        request_id: Option<String>,
+        /// Request that failed, when it was sent by the resolver
+        context: Option<Box<RequestContext>>,
+    },
 
     /// Invalid configuration
     #[error("Invalid configuration: {0}")]
@@ -48,33 +88,363 @@ pub enum MvrError {
     /// Concurrent request limit exceeded
     #[error("Too many concurrent requests. Maximum allowed: {max_concurrent}")]
     TooManyConcurrentRequests { max_concurrent: usize },
+
+    /// Response was well-formed JSON but lacked the expected field
+    #[error("Invalid response from MVR: {0}")]
+    InvalidResponse(String),
+
+    /// Response carried a value that is not a valid address or type signature
+    #[error("Malformed response from MVR: {0}")]
+    MalformedResponse(String),
+
+    /// HTTP client could not be initialized
+    #[error("Failed to initialize HTTP client: {0}")]
+    ClientInit(String),
+
+    /// Network resolution was compiled out
+    #[error("Cannot resolve '{0}': network support is disabled, provide it through overrides")]
+    NetworkDisabled(String),
+
+    /// Resolved package does not expose a callable module function
+    #[error("Move call target not found: {0}")]
+    MoveTargetNotFound(String),
+
+    /// Call arguments do not match the resolved function signature
+    #[error("Invalid call arguments: {0}")]
+    InvalidArguments(String),
+
+    /// SuiNS name has no target address
+    #[error("Name '{0}' not found in SuiNS")]
+    NameNotFound(String),
+
+    /// Retry skipped because the resolver's retry budget is used up
+    ///
+    /// Carries the error that would have been retried. Not retryable: retrying it
+    /// is the traffic the budget exists to prevent.
+    #[error("Retry budget exhausted, not retrying: {0}")]
+    RetryBudgetExhausted(#[source] Box<MvrError>),
+
+    /// Request that still failed after internal retries
+    ///
+    /// Code, kind and the `is_*` checks are those of the final attempt's error,
+    /// see [`MvrError::last_error`].
+    #[error("{last} (after {} attempts in {elapsed:?})", .earlier.len() + 1)]
+    Retried {
+        /// Error of the final attempt
+        #[source]
+        last: Box<MvrError>,
+        /// Errors of the earlier attempts, oldest first
+        earlier: Vec<MvrError>,
+        /// Time from the first attempt until giving up
+        elapsed: std::time::Duration,
+    },
 }
 
 impl MvrError {
+    /// Every code [`MvrError::code`] can return
+    pub const CODES: &'static [&'static str] = &[
+        "MVR_E_HTTP",
+        "MVR_E_JSON",
+        "MVR_E_PACKAGE_NOT_FOUND",
+        "MVR_E_TYPE_NOT_FOUND",
+        "MVR_E_CACHE",
+        "MVR_E_INVALID_PACKAGE_NAME",
+        "MVR_E_INVALID_TYPE_NAME",
+        "MVR_E_INVALID_TARGET",
+        "MVR_E_TIMEOUT",
+        "MVR_E_RATE_LIMITED",
+        "MVR_E_SERVER",
+        "MVR_E_CONFIG",
+        "MVR_E_TOO_MANY_REQUESTS",
+        "MVR_E_INVALID_RESPONSE",
+        "MVR_E_CLIENT_INIT",
+        "MVR_E_NETWORK_DISABLED",
+        "MVR_E_MOVE_TARGET_NOT_FOUND",
+        "MVR_E_INVALID_ARGUMENTS",
+        "MVR_E_NAME_NOT_FOUND",
+        "MVR_E_RETRY_BUDGET_EXHAUSTED",
+        "MVR_E_MALFORMED_RESPONSE",
+    ];
+
+    /// Stable, machine-readable code identifying the kind of error
+    ///
+    /// Codes never change between releases, unlike the English `Display` messages,
+    /// so frontends can map them to translated messages.
+    pub fn code(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "network")]
+            MvrError::HttpError(_) => "MVR_E_HTTP",
+            MvrError::JsonError(_) => "MVR_E_JSON",
+            MvrError::PackageNotFound { .. } => "MVR_E_PACKAGE_NOT_FOUND",
+            MvrError::TypeNotFound(_) => "MVR_E_TYPE_NOT_FOUND",
+            MvrError::CacheError(_) => "MVR_E_CACHE",
+            MvrError::InvalidPackageName(_) => "MVR_E_INVALID_PACKAGE_NAME",
+            MvrError::InvalidTypeName(_) => "MVR_E_INVALID_TYPE_NAME",
+            MvrError::InvalidTarget { .. } => "MVR_E_INVALID_TARGET",
+            MvrError::Timeout { .. } => "MVR_E_TIMEOUT",
+            MvrError::RateLimitExceeded { .. } => "MVR_E_RATE_LIMITED",
+            MvrError::ServerError { .. } => "MVR_E_SERVER",
+            MvrError::ConfigError(_) => "MVR_E_CONFIG",
+            MvrError::TooManyConcurrentRequests { .. } => "MVR_E_TOO_MANY_REQUESTS",
+            MvrError::InvalidResponse(_) => "MVR_E_INVALID_RESPONSE",
+            MvrError::ClientInit(_) => "MVR_E_CLIENT_INIT",
+            MvrError::NetworkDisabled(_) => "MVR_E_NETWORK_DISABLED",
+            MvrError::MoveTargetNotFound(_) => "MVR_E_MOVE_TARGET_NOT_FOUND",
+            MvrError::InvalidArguments(_) => "MVR_E_INVALID_ARGUMENTS",
+            MvrError::NameNotFound(_) => "MVR_E_NAME_NOT_FOUND",
+            MvrError::RetryBudgetExhausted(_) => "MVR_E_RETRY_BUDGET_EXHAUSTED",
+            MvrError::MalformedResponse(_) => "MVR_E_MALFORMED_RESPONSE",
+            MvrError::Retried { last, .. } => last.code(),
+        }
+    }
+
+    /// Category of the error, for branching and metrics across variants
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            #[cfg(feature = "network")]
+            MvrError::HttpError(_) => ErrorKind::Network,
+            MvrError::JsonError(_)
+            | MvrError::InvalidResponse(_)
+            | MvrError::MalformedResponse(_) => ErrorKind::InvalidResponse,
+            MvrError::PackageNotFound { .. }
+            | MvrError::TypeNotFound(_)
+            | MvrError::MoveTargetNotFound(_)
+            | MvrError::NameNotFound(_) => ErrorKind::NotFound,
+            MvrError::CacheError(_) => ErrorKind::Cache,
+            MvrError::InvalidPackageName(_)
+            | MvrError::InvalidTypeName(_)
+            | MvrError::InvalidTarget { .. }
+            | MvrError::InvalidArguments(_) => ErrorKind::InvalidInput,
+            MvrError::Timeout { .. } => ErrorKind::Timeout,
+            MvrError::RateLimitExceeded { .. } => ErrorKind::RateLimited,
+            MvrError::ServerError { .. } => ErrorKind::Server,
+            MvrError::ConfigError(_) | MvrError::ClientInit(_) => ErrorKind::Config,
+            MvrError::TooManyConcurrentRequests { .. } => ErrorKind::Overloaded,
+            MvrError::NetworkDisabled(_) => ErrorKind::Unsupported,
+            MvrError::RetryBudgetExhausted(_) => ErrorKind::Overloaded,
+            MvrError::Retried { last, .. } => last.kind(),
+        }
+    }
+
+    /// What is wrong with the name of an `InvalidPackageName` or `InvalidTypeName`
+    ///
+    /// `None` for other errors, and for names that are well-formed but were rejected
+    /// by a stricter parser such as the Sui SDK's.
+    pub fn name_error(&self) -> Option<NameError> {
+        match self {
+            MvrError::InvalidPackageName(name) => check_package_name(name).err(),
+            MvrError::InvalidTypeName(name) => check_type_name(name).err(),
+            _ => None,
+        }
+    }
+
+    /// Package that the registry does not know
+    pub fn package_not_found(name: String) -> Self {
+        MvrError::PackageNotFound {
+            name,
+            suggestions: Vec::new(),
+        }
+    }
+
+    /// Type that the registry does not know
+    pub fn type_not_found(name: String) -> Self {
+        MvrError::TypeNotFound(name)
+    }
+
+    /// Move call target that is malformed, with what is wrong with it
+    pub fn invalid_target(target: String, reason: String) -> Self {
+        MvrError::InvalidTarget { target, reason }
+    }
+
+    /// Non-success HTTP status from a registry
+    ///
+    /// 5xx statuses are retryable, 4xx statuses are client errors. `message` is cut to
+    /// [`MAX_ERROR_BODY_LEN`] bytes so large error pages stay readable in logs.
+    pub fn server(status_code: u16, message: String) -> Self {
+        MvrError::ServerError {
+            status_code,
+            message: truncate_body(message),
+            request_id: None,
+            context: None,
+        }
+    }
+
+    /// Rate limited by a registry, retryable after `retry_after` (rounded down to seconds)
+    pub fn rate_limited(retry_after: std::time::Duration) -> Self {
+        MvrError::RateLimitExceeded {
+            retry_after_secs: retry_after.as_secs(),
+            request_id: None,
+            context: None,
+        }
+    }
+
+    /// Request abandoned after `timeout` (rounded down to seconds)
+    pub fn timeout(timeout: std::time::Duration) -> Self {
+        MvrError::Timeout {
+            timeout_secs: timeout.as_secs(),
+        }
+    }
+
+    /// Registry answer that could not be interpreted
+    pub fn invalid_response(message: String) -> Self {
+        MvrError::InvalidResponse(message)
+    }
+
+    /// Invalid configuration of a resolver or backend
+    pub fn config(message: String) -> Self {
+        MvrError::ConfigError(message)
+    }
+
+    /// Failure of a cache outside the resolver's own
+    pub fn cache(message: String) -> Self {
+        MvrError::CacheError(message)
+    }
+
+    /// Attach the `X-Request-Id` of the request that failed
+    ///
+    /// Only registry responses carry a request ID; other errors are returned unchanged.
+    pub fn with_request_id(mut self, id: impl Into<String>) -> Self {
+        if let MvrError::RateLimitExceeded { request_id, .. }
+        | MvrError::ServerError { request_id, .. } = &mut self
+        {
+            *request_id = Some(id.into());
+        }
+        self
+    }
+
+    /// `X-Request-Id` of the request that failed, to correlate with registry logs
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            MvrError::RateLimitExceeded { request_id, .. }
+            | MvrError::ServerError { request_id, .. } => request_id.as_deref(),
+            MvrError::Retried { last, .. } | MvrError::RetryBudgetExhausted(last) => {
+                last.request_id()
+            }
+            _ => None,
+        }
+    }
+
+    /// Attach the request that failed
+    ///
+    /// Only registry responses carry a request context; other errors are returned unchanged.
+    pub fn with_context(mut self, request: RequestContext) -> Self {
+        if let MvrError::RateLimitExceeded { context, .. } | MvrError::ServerError { context, .. } =
+            &mut self
+        {
+            *context = Some(Box::new(request));
+        }
+        self
+    }
+
+    /// Method, URL and attempt of the request that failed
+    pub fn context(&self) -> Option<&RequestContext> {
+        match self {
+            MvrError::RateLimitExceeded { context, .. } | MvrError::ServerError { context, .. } => {
+                context.as_deref()
+            }
+            MvrError::Retried { last, .. } | MvrError::RetryBudgetExhausted(last) => last.context(),
+            _ => None,
+        }
+    }
+
+    /// Count `retries` more attempts in the request context, if there is one
+    #[cfg(feature = "network")]
+    pub(crate) fn retried(mut self, retries: u32) -> Self {
+        if let Some(context) = self.context_mut() {
+            context.attempt += retries;
+        }
+        self
+    }
+
+    #[cfg(feature = "network")]
+    fn context_mut(&mut self) -> Option<&mut RequestContext> {
+        match self {
+            MvrError::RateLimitExceeded { context, .. } | MvrError::ServerError { context, .. } => {
+                context.as_deref_mut()
+            }
+            MvrError::Retried { last, .. } => last.context_mut(),
+            _ => None,
+        }
+    }
+
+    /// `self` as the final attempt after the failed `earlier` ones
+    ///
+    /// Nested retries are flattened into one list of attempts. Without earlier
+    /// attempts the error is returned unchanged.
+    #[cfg(feature = "network")]
+    pub(crate) fn after_attempts(
+        self,
+        earlier: Vec<MvrError>,
+        elapsed: std::time::Duration,
+    ) -> Self {
+        let mut attempts = Vec::new();
+        for error in earlier.into_iter().chain([self]) {
+            match error {
+                MvrError::Retried { last, earlier, .. } => {
+                    attempts.extend(earlier);
+                    attempts.push(*last);
+                }
+                error => attempts.push(error),
+            }
+        }
+        let last = attempts.pop().expect("the final attempt is always present");
+        if attempts.is_empty() {
+            return last;
+        }
+        MvrError::Retried {
+            last: Box::new(last),
+            earlier: attempts,
+            elapsed,
+        }
+    }
+
+    /// Error of the final attempt of a retried request, `self` otherwise
+    pub fn last_error(&self) -> &MvrError {
+        match self {
+            MvrError::Retried { last, .. } => last,
+            error => error,
+        }
+    }
+
+    /// Number of attempts made, 1 unless the resolver retried internally
+    pub fn attempts(&self) -> usize {
+        match self {
+            MvrError::Retried { earlier, .. } => earlier.len() + 1,
+            _ => 1,
+        }
+    }
+
     /// Check if the error is retryable
     pub fn is_retryable(&self) -> bool {
         match self {
+            #[cfg(feature = "network")]
             MvrError::HttpError(_) => true,
             MvrError::Timeout { .. } => true,
             MvrError::RateLimitExceeded { .. } => true, // Rate limits are retryable after waiting
             MvrError::ServerError { status_code, .. } => *status_code >= 500,
+            MvrError::Retried { last, .. } => last.is_retryable(),
             _ => false,
         }
     }
 
     /// Check if the error is due to rate limiting
     pub fn is_rate_limited(&self) -> bool {
-        matches!(self, MvrError::RateLimitExceeded { .. })
+        matches!(self.last_error(), MvrError::RateLimitExceeded { .. })
     }
 
     /// Check if the error is a client error (4xx)
     pub fn is_client_error(&self) -> bool {
         match self {
-            MvrError::PackageNotFound(_) => true,
+            MvrError::PackageNotFound { .. } => true,
             MvrError::TypeNotFound(_) => true,
             MvrError::InvalidPackageName(_) => true,
             MvrError::InvalidTypeName(_) => true,
+            MvrError::InvalidTarget { .. } => true,
+            MvrError::MoveTargetNotFound(_) => true,
+            MvrError::InvalidArguments(_) => true,
+            MvrError::NameNotFound(_) => true,
             MvrError::ServerError { status_code, .. } => *status_code >= 400 && *status_code < 500,
+            MvrError::Retried { last, .. } => last.is_client_error(),
             _ => false,
         }
     }
@@ -82,71 +452,400 @@ impl MvrError {
     /// Get retry delay for retryable errors
     pub fn retry_delay(&self) -> Option<std::time::Duration> {
         match self {
-            MvrError::RateLimitExceeded { retry_after_secs } => {
-                Some(std::time::Duration::from_secs(*retry_after_secs))
-            }
-            MvrError::HttpError(_) | MvrError::Timeout { .. } => {
-                Some(std::time::Duration::from_secs(1))
-            }
+            MvrError::RateLimitExceeded {
+                retry_after_secs, ..
+            } => Some(std::time::Duration::from_secs(*retry_after_secs)),
+            #[cfg(feature = "network")]
+            MvrError::HttpError(_) => Some(std::time::Duration::from_secs(1)),
+            MvrError::Timeout { .. } => Some(std::time::Duration::from_secs(1)),
             MvrError::ServerError { status_code, .. } if *status_code >= 500 => {
                 Some(std::time::Duration::from_secs(2))
             }
+            MvrError::Retried { last, .. } => last.retry_delay(),
             _ => None,
         }
     }
 }
 
+/// Category of an [`MvrError`], see [`MvrError::kind`]
+///
+/// Several variants share a kind, e.g. every "not found" error is
+/// [`ErrorKind::NotFound`]. Kinds and their codes never change between releases;
+/// new kinds may be added, so matches need a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The package, type, function or name does not exist
+    NotFound,
+    /// A name or argument is malformed
+    InvalidInput,
+    /// The request could not be sent or its response not read
+    Network,
+    /// The request did not complete in time
+    Timeout,
+    /// The registry asked to slow down
+    RateLimited,
+    /// The registry answered with an unexpected HTTP status
+    Server,
+    /// The registry answer could not be interpreted
+    InvalidResponse,
+    /// The resolver or a backend is misconfigured
+    Config,
+    /// A cache operation failed
+    Cache,
+    /// Too many requests are already in flight
+    Overloaded,
+    /// The operation needs a capability that was compiled out
+    Unsupported,
+}
+
+impl ErrorKind {
+    /// Every kind, in code order
+    pub const ALL: &'static [ErrorKind] = &[
+        ErrorKind::NotFound,
+        ErrorKind::InvalidInput,
+        ErrorKind::Network,
+        ErrorKind::Timeout,
+        ErrorKind::RateLimited,
+        ErrorKind::Server,
+        ErrorKind::InvalidResponse,
+        ErrorKind::Config,
+        ErrorKind::Cache,
+        ErrorKind::Overloaded,
+        ErrorKind::Unsupported,
+    ];
+
+    /// Stable snake_case name, e.g. `not_found`, for logs and metric labels
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::InvalidInput => "invalid_input",
+            ErrorKind::Network => "network",
+            ErrorKind::Timeout => "timeout",
+            ErrorKind::RateLimited => "rate_limited",
+            ErrorKind::Server => "server",
+            ErrorKind::InvalidResponse => "invalid_response",
+            ErrorKind::Config => "config",
+            ErrorKind::Cache => "cache",
+            ErrorKind::Overloaded => "overloaded",
+            ErrorKind::Unsupported => "unsupported",
+        }
+    }
+
+    /// Stable numeric code, for protocols that carry integers
+    pub fn code(self) -> u16 {
+        match self {
+            ErrorKind::NotFound => 1,
+            ErrorKind::InvalidInput => 2,
+            ErrorKind::Network => 3,
+            ErrorKind::Timeout => 4,
+            ErrorKind::RateLimited => 5,
+            ErrorKind::Server => 6,
+            ErrorKind::InvalidResponse => 7,
+            ErrorKind::Config => 8,
+            ErrorKind::Cache => 9,
+            ErrorKind::Overloaded => 10,
+            ErrorKind::Unsupported => 11,
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// ` — did you mean ...?` for error messages, empty without suggestions
+fn did_you_mean(suggestions: &[String]) -> String {
+    match suggestions {
+        [] => String::new(),
+        [only] => format!(" — did you mean `{only}`?"),
+        [rest @ .., last] => {
+            let rest: Vec<String> = rest.iter().map(|s| format!("`{s}`")).collect();
+            format!(" — did you mean {} or `{last}`?", rest.join(", "))
+        }
+    }
+}
+
+/// Longest response body kept in [`MvrError::ServerError`], in bytes
+pub const MAX_ERROR_BODY_LEN: usize = 512;
+
+/// Request behind an HTTP error, for debugging without a packet capture
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestContext {
+    /// HTTP method, e.g. `GET`
+    pub method: String,
+    /// Scheme, host and port of the registry or fullnode, e.g. `https://mainnet.mvr.mystenlabs.com`
+    pub endpoint: String,
+    /// Path and query of the request, e.g. `/v1/resolution/@suifrens/core`
+    pub path: String,
+    /// Attempt that failed, starting at 1 and counting rate-limit and batch retries
+    pub attempt: u32,
+}
+
+impl fmt::Display for RequestContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}{}, attempt {}",
+            self.method, self.endpoint, self.path, self.attempt
+        )
+    }
+}
+
+/// ` (<request>, request <id>)` for error messages, empty without either
+fn request_suffix(context: &Option<Box<RequestContext>>, request_id: &Option<String>) -> String {
+    let parts: Vec<String> = context
+        .iter()
+        .map(|context| context.to_string())
+        .chain(request_id.iter().map(|id| format!("request {id}")))
+        .collect();
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", parts.join(", "))
+    }
+}
+
+/// `body` cut to at most [`MAX_ERROR_BODY_LEN`] bytes on a character boundary
+fn truncate_body(mut body: String) -> String {
+    if body.len() > MAX_ERROR_BODY_LEN {
+        let mut end = MAX_ERROR_BODY_LEN;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        body.truncate(end);
+        body.push_str("...");
+    }
+    body
+}
+
 /// Result type alias for MVR operations
 pub type MvrResult<T> = Result<T, MvrError>;
 
-/// Helper function to validate package name format
-pub(crate) fn validate_package_name(name: &str) -> MvrResult<()> {
-    if !name.starts_with('@') {
-        return Err(MvrError::InvalidPackageName(name.to_string()));
-    }
+/// Why a package or type name is malformed, see [`MvrError::name_error`]
+///
+/// Character indices count characters, not bytes, from the start of the name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NameError {
+    /// The name does not start with `@`
+    MissingAt,
+    /// Nothing between `@` and `/`, e.g. `@/core`
+    EmptyNamespace,
+    /// No `/` separating namespace and package, e.g. `@suifrens`
+    MissingPackage,
+    /// Nothing after the `/`, e.g. `@suifrens/`
+    EmptyPackage,
+    /// More than 8 `/`-separated segments, i.e. more than 6 levels of subnames
+    TooManySegments { segments: usize },
+    /// An empty subname, e.g. `@suifrens/core/` or `@suifrens/core//accessories`
+    EmptySubname { index: usize },
+    /// A character other than a lowercase ASCII letter, digit or `-`, or a `.` in the
+    /// package name
+    IllegalCharacter { character: char, index: usize },
+    /// An uppercase letter, names are lowercase, e.g. `@SuiFrens/core`
+    UppercaseCharacter { character: char, index: usize },
+    /// An empty `.`-separated label in the namespace, e.g. `@suifrens./core`
+    EmptyLabel { index: usize },
+    /// A namespace label or package name starting or ending with `-`
+    EdgeHyphen { index: usize },
+    /// A namespace label longer than 63 characters, or a package name or subname longer
+    /// than 64
+    LabelTooLong { length: usize, max: usize },
+    /// A type name without `::module::Type` after the package
+    MissingTypePath,
+    /// An empty `::`-separated part of a type name, e.g. `@suifrens/core::::Type`
+    EmptyTypeSegment { index: usize },
+}
 
-    let without_at = &name[1..];
-    if !without_at.contains('/') {
-        return Err(MvrError::InvalidPackageName(name.to_string()));
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NameError::MissingAt => f.write_str("missing '@' at the start"),
+            NameError::EmptyNamespace => f.write_str("empty namespace"),
+            NameError::MissingPackage => f.write_str("missing '/' between namespace and package"),
+            NameError::EmptyPackage => f.write_str("empty package name"),
+            NameError::TooManySegments { segments } => {
+                write!(
+                    f,
+                    "expected at most {MAX_NAME_SEGMENTS} '/'-separated segments, found {segments}"
+                )
+            }
+            NameError::IllegalCharacter { character, index } => {
+                write!(f, "illegal character {character:?} at index {index}")
+            }
+            NameError::UppercaseCharacter { character, index } => {
+                write!(
+                    f,
+                    "uppercase character {character:?} at index {index}, names are lowercase"
+                )
+            }
+            NameError::EmptySubname { index } => write!(f, "empty subname at index {index}"),
+            NameError::EmptyLabel { index } => write!(f, "empty namespace label at index {index}"),
+            NameError::EdgeHyphen { index } => {
+                write!(f, "hyphen at index {index} starts or ends a label")
+            }
+            NameError::LabelTooLong { length, max } => {
+                write!(
+                    f,
+                    "label is {length} characters long, at most {max} are allowed"
+                )
+            }
+            NameError::MissingTypePath => f.write_str("missing '::module::Type' after the package"),
+            NameError::EmptyTypeSegment { index } => write!(f, "empty segment at index {index}"),
+        }
     }
+}
+
+/// Longest label of a namespace, which is a SuiNS name
+const MAX_NAMESPACE_LABEL_LEN: usize = 63;
+/// Longest package name the registry accepts
+const MAX_PACKAGE_NAME_LEN: usize = 64;
 
-    let parts: Vec<&str> = without_at.split('/').collect();
-    if parts.len() != 2 || parts[0].is_empty() || parts[1].is_empty() {
-        return Err(MvrError::InvalidPackageName(name.to_string()));
+/// Most `/`-separated segments of a name: namespace, package and up to 6 subnames
+pub(crate) const MAX_NAME_SEGMENTS: usize = 8;
+
+/// Check that `name` has the form `@namespace/package` or `@namespace/package/sub...`
+/// and follows the registry rules
+///
+/// The package and its subnames use lowercase ASCII letters, digits and inner hyphens;
+/// the namespace may consist of several `.`-separated labels of the same characters.
+pub(crate) fn check_package_name(name: &str) -> Result<(), NameError> {
+    let without_at = name.strip_prefix('@').ok_or(NameError::MissingAt)?;
+    let mut segments = without_at.split('/');
+    let namespace = segments.next().unwrap_or_default();
+    let package = segments.next().ok_or(NameError::MissingPackage)?;
+    if namespace.is_empty() {
+        return Err(NameError::EmptyNamespace);
+    }
+    let count = without_at.split('/').count();
+    if count > MAX_NAME_SEGMENTS {
+        return Err(NameError::TooManySegments { segments: count });
+    }
+    if package.is_empty() {
+        return Err(NameError::EmptyPackage);
     }
 
+    let mut index = 1;
+    for label in namespace.split('.') {
+        check_label(label, index, MAX_NAMESPACE_LABEL_LEN)?;
+        index += label.chars().count() + 1;
+    }
+    check_label(package, index, MAX_PACKAGE_NAME_LEN)?;
+    index += package.chars().count() + 1;
+    for subname in segments {
+        if subname.is_empty() {
+            return Err(NameError::EmptySubname { index });
+        }
+        check_label(subname, index, MAX_PACKAGE_NAME_LEN)?;
+        index += subname.chars().count() + 1;
+    }
     Ok(())
 }
 
-/// Helper function to validate type name format
-pub(crate) fn validate_type_name(name: &str) -> MvrResult<()> {
-    if !name.starts_with('@') {
-        return Err(MvrError::InvalidTypeName(name.to_string()));
+/// Check one namespace label or the package name, which starts at character `start`
+fn check_label(label: &str, start: usize, max: usize) -> Result<(), NameError> {
+    for (index, character) in (start..).zip(label.chars()) {
+        if character.is_ascii_uppercase() {
+            return Err(NameError::UppercaseCharacter { character, index });
+        }
+        if !(character.is_ascii_lowercase() || character.is_ascii_digit() || character == '-') {
+            return Err(NameError::IllegalCharacter { character, index });
+        }
     }
 
-    if !name.contains("::") {
-        return Err(MvrError::InvalidTypeName(name.to_string()));
+    let length = label.chars().count();
+    if length == 0 {
+        return Err(NameError::EmptyLabel { index: start });
     }
+    if label.starts_with('-') {
+        return Err(NameError::EdgeHyphen { index: start });
+    }
+    if label.ends_with('-') {
+        return Err(NameError::EdgeHyphen {
+            index: start + length - 1,
+        });
+    }
+    if length > max {
+        return Err(NameError::LabelTooLong { length, max });
+    }
+    Ok(())
+}
 
-    // Split on :: to get parts
+/// Check that `name` has the form `@namespace/package::module::Type`
+pub(crate) fn check_type_name(name: &str) -> Result<(), NameError> {
+    if !name.starts_with('@') {
+        return Err(NameError::MissingAt);
+    }
     let parts: Vec<&str> = name.split("::").collect();
     if parts.len() < 3 {
-        return Err(MvrError::InvalidTypeName(name.to_string()));
+        return Err(NameError::MissingTypePath);
     }
-
-    // First part should be @namespace/package
-    validate_package_name(parts[0])?;
+    check_package_name(parts[0])?;
 
     // Remaining parts should be non-empty (module::Type)
+    let mut index = parts[0].chars().count();
     for part in &parts[1..] {
+        index += 2;
         if part.is_empty() {
-            return Err(MvrError::InvalidTypeName(name.to_string()));
+            return Err(NameError::EmptyTypeSegment { index });
         }
+        index += part.chars().count();
     }
-
     Ok(())
 }
 
+/// `: <reason>` for error messages, empty when the name is well-formed
+fn reason_suffix(check: Result<(), NameError>) -> String {
+    check.err().map(|e| format!(": {e}")).unwrap_or_default()
+}
+
+/// Trim whitespace, drop a trailing `/` and lowercase a package name
+///
+/// Registry names are lowercase, so `" @Suifrens/Core/ "` becomes `"@suifrens/core"`.
+/// The name is borrowed when nothing changes.
+pub(crate) fn normalize_package_name(name: &str) -> Cow<'_, str> {
+    let trimmed = name.trim();
+    let trimmed = trimmed.strip_suffix('/').unwrap_or(trimmed);
+    if trimmed.bytes().any(|b| b.is_ascii_uppercase()) {
+        Cow::Owned(trimmed.to_ascii_lowercase())
+    } else {
+        Cow::Borrowed(trimmed)
+    }
+}
+
+/// Normalize the package part of a type name like [`normalize_package_name`]
+///
+/// Module and type names are case-sensitive in Move and are kept as they are.
+pub(crate) fn normalize_type_name(name: &str) -> Cow<'_, str> {
+    let trimmed = name.trim();
+    let Some((package, path)) = trimmed.split_once("::") else {
+        return Cow::Borrowed(trimmed);
+    };
+    match normalize_package_name(package) {
+        Cow::Borrowed(normalized) if normalized.len() == package.len() => Cow::Borrowed(trimmed),
+        normalized => Cow::Owned(format!("{normalized}::{path}")),
+    }
+}
+
+/// Helper function to validate package name format
+pub(crate) fn validate_package_name(name: &str) -> MvrResult<()> {
+    check_package_name(name).map_err(|_| MvrError::InvalidPackageName(name.to_string()))
+}
+
+/// Helper function to validate type name format
+pub(crate) fn validate_type_name(name: &str) -> MvrResult<()> {
+    let invalid = || MvrError::InvalidTypeName(name.to_string());
+    if !name.starts_with('@') || name.split("::").count() < 3 {
+        return Err(invalid());
+    }
+    // A malformed package part is reported as an invalid package name
+    validate_package_name(name.split("::").next().unwrap_or_default())?;
+    check_type_name(name).map_err(|_| invalid())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -157,6 +856,10 @@ mod tests {
         assert!(validate_package_name("@suifrens/core").is_ok());
         assert!(validate_package_name("@namespace/package").is_ok());
         assert!(validate_package_name("@test/pkg").is_ok());
+        assert!(validate_package_name("@pkg.suifrens/core-v2").is_ok());
+        assert!(validate_package_name("@suifrens/core/accessories").is_ok());
+        assert!(validate_package_name("@ns/pkg/a/b/c/d/e/f").is_ok());
+        assert!(validate_package_name(&format!("@{}/core", "a".repeat(63))).is_ok());
 
         // Invalid names
         assert!(validate_package_name("suifrens/core").is_err()); // Missing @
@@ -172,6 +875,7 @@ mod tests {
         assert!(validate_type_name("@ns/pkg::mod::Type<T>").is_ok());
         assert!(validate_type_name("@test/package::module::TestType").is_ok());
         assert!(validate_type_name("@batch/pkg1::module::Type1").is_ok());
+        assert!(validate_type_name("@suifrens/core/accessories::hat::Hat").is_ok());
 
         // Invalid names
         assert!(validate_type_name("@suifrens/core").is_err()); // Missing ::
@@ -179,9 +883,133 @@ mod tests {
         assert!(validate_type_name("@ns/pkg::Type").is_err()); // Not enough parts (missing module)
     }
 
+    #[test]
+    fn test_normalize_names() {
+        assert_eq!(normalize_package_name(" @Suifrens/Core "), "@suifrens/core");
+        assert_eq!(
+            normalize_package_name("@suifrens/core/\n"),
+            "@suifrens/core"
+        );
+        assert!(matches!(
+            normalize_package_name("@suifrens/core"),
+            Cow::Borrowed("@suifrens/core")
+        ));
+        assert_eq!(
+            normalize_type_name(" @SuiFrens/core::SuiFren::SuiFren<T> "),
+            "@suifrens/core::SuiFren::SuiFren<T>"
+        );
+        assert!(matches!(
+            normalize_type_name("@ns/pkg::m::Type"),
+            Cow::Borrowed("@ns/pkg::m::Type")
+        ));
+        assert_eq!(normalize_type_name("@ns/pkg/::m::T"), "@ns/pkg::m::T");
+    }
+
+    #[test]
+    fn test_name_errors() {
+        for (name, expected) in [
+            ("suifrens/core", NameError::MissingAt),
+            ("@suifrens", NameError::MissingPackage),
+            ("@/core", NameError::EmptyNamespace),
+            ("@ns/", NameError::EmptyPackage),
+            (
+                "@ns/pkg/a/b/c/d/e/f/g",
+                NameError::TooManySegments { segments: 9 },
+            ),
+            ("@ns/pkg/", NameError::EmptySubname { index: 8 }),
+            ("@ns/pkg//sub", NameError::EmptySubname { index: 8 }),
+            (
+                "@ns/pkg/Sub",
+                NameError::UppercaseCharacter {
+                    character: 'S',
+                    index: 8,
+                },
+            ),
+            (
+                "@sui frens/core",
+                NameError::IllegalCharacter {
+                    character: ' ',
+                    index: 4,
+                },
+            ),
+            (
+                "@ns/pk€g",
+                NameError::IllegalCharacter {
+                    character: '€',
+                    index: 6,
+                },
+            ),
+            (
+                "@ns/my_pkg",
+                NameError::IllegalCharacter {
+                    character: '_',
+                    index: 6,
+                },
+            ),
+            (
+                "@ns/pkg.v2",
+                NameError::IllegalCharacter {
+                    character: '.',
+                    index: 7,
+                },
+            ),
+            (
+                "@ns/Core",
+                NameError::UppercaseCharacter {
+                    character: 'C',
+                    index: 4,
+                },
+            ),
+            ("@ns..sui/pkg", NameError::EmptyLabel { index: 4 }),
+            ("@-ns/pkg", NameError::EdgeHyphen { index: 1 }),
+            ("@sub.ns-/pkg", NameError::EdgeHyphen { index: 7 }),
+            ("@ns/pkg-", NameError::EdgeHyphen { index: 7 }),
+            (
+                "@ns/aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+                NameError::LabelTooLong {
+                    length: 65,
+                    max: 64,
+                },
+            ),
+        ] {
+            assert_eq!(check_package_name(name), Err(expected.clone()), "{name}");
+            assert_eq!(
+                MvrError::InvalidPackageName(name.to_string()).name_error(),
+                Some(expected)
+            );
+        }
+        assert_eq!(
+            check_type_name("@ns/pkg::Type"),
+            Err(NameError::MissingTypePath)
+        );
+        assert_eq!(
+            check_type_name("@ns/pkg::::Type"),
+            Err(NameError::EmptyTypeSegment { index: 9 })
+        );
+        assert_eq!(
+            check_type_name("@/pkg::m::T"),
+            Err(NameError::EmptyNamespace)
+        );
+        assert_eq!(check_type_name("@ns/pkg::m::Type<T, U>"), Ok(()));
+        assert_eq!(
+            MvrError::TypeNotFound("@ns/pkg".to_string()).name_error(),
+            None
+        );
+
+        assert_eq!(
+            MvrError::InvalidPackageName("@ns/".to_string()).to_string(),
+            "Invalid package name format: '@ns/': empty package name. Expected format: @namespace/package"
+        );
+        // Names rejected by stricter parsers have no reason to add
+        assert_eq!(
+            MvrError::InvalidTypeName("@ns/pkg::m::T".to_string()).to_string(),
+            "Invalid type name format: '@ns/pkg::m::T'. Expected format: @namespace/package::module::Type"
+        );
+    }
+
     #[test]
     fn test_error_properties() {
-        let error = MvrError::PackageNotFound("test".to_string());
+        let error = MvrError::package_not_found("test".to_string());
         assert!(error.is_client_error());
         assert!(!error.is_retryable());
 
@@ -189,9 +1017,7 @@ mod tests {
         assert!(error.is_retryable());
         assert!(!error.is_client_error());
 
-        let error = MvrError::RateLimitExceeded {
-            retry_after_secs: 60,
-        };
+        let error = MvrError::rate_limited(std::time::Duration::from_secs(60));
         assert!(error.is_rate_limited());
         assert!(error.is_retryable()); // Rate limits should be retryable
         assert_eq!(
@@ -199,4 +1025,143 @@ mod tests {
             Some(std::time::Duration::from_secs(60))
         );
     }
+
+    #[test]
+    fn test_error_constructors() {
+        let error = MvrError::server(503, "unavailable".to_string());
+        assert!(error.is_retryable());
+        assert_eq!(error.code(), "MVR_E_SERVER");
+        assert!(MvrError::server(403, "forbidden".to_string()).is_client_error());
+
+        let error = MvrError::rate_limited(std::time::Duration::from_millis(2500));
+        assert_eq!(error.retry_delay(), Some(std::time::Duration::from_secs(2)));
+        assert!(matches!(
+            MvrError::timeout(std::time::Duration::from_secs(30)),
+            MvrError::Timeout { timeout_secs: 30 }
+        ));
+        assert!(MvrError::package_not_found("@test/pkg".to_string()).is_client_error());
+        assert_eq!(
+            MvrError::type_not_found("@test/pkg::m::T".to_string()).code(),
+            "MVR_E_TYPE_NOT_FOUND"
+        );
+        assert_eq!(
+            MvrError::invalid_response("empty".to_string()).code(),
+            "MVR_E_INVALID_RESPONSE"
+        );
+        assert_eq!(MvrError::config("bad".to_string()).code(), "MVR_E_CONFIG");
+        assert_eq!(MvrError::cache("full".to_string()).code(), "MVR_E_CACHE");
+    }
+
+    #[test]
+    fn test_error_kind_codes() {
+        for (index, kind) in ErrorKind::ALL.iter().enumerate() {
+            assert_eq!(usize::from(kind.code()), index + 1);
+            assert_eq!(kind.to_string(), kind.as_str());
+        }
+        let mut names: Vec<&str> = ErrorKind::ALL.iter().map(|kind| kind.as_str()).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), ErrorKind::ALL.len());
+    }
+
+    #[cfg(feature = "network")]
+    #[test]
+    fn test_after_attempts() {
+        let elapsed = std::time::Duration::from_millis(1500);
+        let single = MvrError::timeout(elapsed).after_attempts(Vec::new(), elapsed);
+        assert!(matches!(single, MvrError::Timeout { .. }));
+        assert_eq!(single.attempts(), 1);
+
+        // Nested retries are flattened into one list of attempts
+        let inner = MvrError::server(503, "unavailable".to_string()).after_attempts(
+            vec![MvrError::rate_limited(std::time::Duration::ZERO)],
+            elapsed,
+        );
+        let error = inner.after_attempts(vec![MvrError::timeout(elapsed)], elapsed * 2);
+        assert_eq!(error.attempts(), 3);
+        assert!(error.is_retryable());
+        assert!(!error.is_rate_limited());
+        assert_eq!(error.kind(), ErrorKind::Server);
+        assert_eq!(
+            error.to_string(),
+            "Server error: 503 - unavailable (after 3 attempts in 3s)"
+        );
+        let MvrError::Retried { earlier, .. } = &error else {
+            panic!("unexpected error: {error}");
+        };
+        assert!(matches!(earlier[0], MvrError::Timeout { .. }));
+        assert!(earlier[1].is_rate_limited());
+    }
+
+    #[test]
+    fn test_request_context() {
+        let context = RequestContext {
+            method: "GET".to_string(),
+            endpoint: "https://mainnet.mvr.mystenlabs.com".to_string(),
+            path: "/v1/resolution/@test/pkg".to_string(),
+            attempt: 3,
+        };
+        let error = MvrError::server(502, "bad gateway".to_string())
+            .with_context(context)
+            .with_request_id("abc");
+        assert_eq!(error.context().unwrap().attempt, 3);
+        assert_eq!(
+            error.to_string(),
+            "Server error: 502 - bad gateway (GET https://mainnet.mvr.mystenlabs.com/v1/resolution/@test/pkg, attempt 3, request abc)"
+        );
+        assert!(MvrError::timeout(std::time::Duration::from_secs(1))
+            .with_context(error.context().unwrap().clone())
+            .context()
+            .is_none());
+
+        let body = truncate_body("é".repeat(MAX_ERROR_BODY_LEN));
+        assert!(body.len() <= MAX_ERROR_BODY_LEN + 3);
+        assert!(body.ends_with("..."));
+        assert_eq!(truncate_body("short".to_string()), "short");
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let errors = [
+            MvrError::package_not_found("@test/pkg".to_string()),
+            MvrError::TypeNotFound("@test/pkg::m::T".to_string()),
+            MvrError::CacheError("poisoned".to_string()),
+            MvrError::InvalidPackageName("bad".to_string()),
+            MvrError::InvalidTypeName("bad".to_string()),
+            MvrError::Timeout { timeout_secs: 30 },
+            MvrError::rate_limited(std::time::Duration::from_secs(1)),
+            MvrError::server(500, String::new()),
+            MvrError::ConfigError("bad".to_string()),
+            MvrError::TooManyConcurrentRequests { max_concurrent: 1 },
+            MvrError::RetryBudgetExhausted(Box::new(MvrError::timeout(
+                std::time::Duration::from_secs(1),
+            ))),
+            MvrError::InvalidResponse("missing".to_string()),
+            MvrError::MalformedResponse("'0xzz' is not a valid address".to_string()),
+            MvrError::ClientInit("tls".to_string()),
+            MvrError::NetworkDisabled("@test/pkg".to_string()),
+            MvrError::MoveTargetNotFound("@test/pkg::m::f".to_string()),
+            MvrError::InvalidArguments("expected 1 arguments, got 0".to_string()),
+            MvrError::NameNotFound("example.sui".to_string()),
+            MvrError::invalid_target(
+                "@ns/pkg::m".to_string(),
+                "expected package::module::function".to_string(),
+            ),
+        ];
+
+        let mut codes: Vec<&str> = errors.iter().map(MvrError::code).collect();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len(), "codes must be unique");
+        assert!(codes.iter().all(|code| MvrError::CODES.contains(code)));
+        for error in &errors {
+            assert!(ErrorKind::ALL.contains(&error.kind()), "{error}");
+        }
+        assert_eq!(errors[0].kind(), ErrorKind::NotFound);
+        assert_eq!(errors[6].kind(), ErrorKind::RateLimited);
+        assert_eq!(
+            MvrError::package_not_found("@test/pkg".to_string()).code(),
+            "MVR_E_PACKAGE_NOT_FOUND"
+        );
+    }
 }
@@ -31,14 +31,74 @@
 //! - **Batch Operations**: Resolve multiple packages/types efficiently
 //! - **Error Handling**: Comprehensive error types and fallback strategies
 
+pub mod abi;
 pub mod cache;
 pub mod error;
+pub mod lint;
+pub mod lockfile;
+#[cfg(feature = "tracing")]
+pub mod logging;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod resolver;
+pub mod resolver_set;
+#[cfg(feature = "signing")]
+pub mod signing;
+#[cfg(feature = "sui-rust-sdk")]
+pub mod sui_sdk;
+#[cfg(feature = "sui-rust-sdk")]
+pub mod templates;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "sui-rust-sdk")]
+pub mod tx_spec;
 pub mod types;
+#[cfg(all(feature = "network", not(target_arch = "wasm32")))]
+pub mod watch;
 
-pub use error::MvrError;
+pub use error::{ErrorKind, MvrError, NameError, RequestContext};
+pub use lockfile::MvrLockfile;
+#[cfg(feature = "metrics")]
+pub use metrics::ResolverMetrics;
+#[cfg(not(target_arch = "wasm32"))]
+pub use resolver::CacheMaintenance;
 pub use resolver::MvrResolver;
-pub use types::{MvrConfig, MvrOverrides};
+pub use resolver_set::{MvrNetwork, MvrResolverSet};
+pub use types::{
+    AppMetadata, AppRecord, DependencyGraph, MvrConfig, MvrOverrides, NameAvailability,
+    OverrideCoverage, PackageVersions, ProxyConfig, RateLimitInfo, RegistryHealth, RegistryRoute,
+    Resolution, ResolutionSource, ResolveOptions, RetryBudget, TypeDisplay, LOCAL_ENDPOINT_ENV,
+    LOCAL_ENDPOINT_FILE_ENV, OVERRIDES_SCHEMA_VERSION,
+};
+
+/// Resolve an MVR name at build time, see the `sui-mvr-macros` crate
+///
+/// ```ignore
+/// // Looked up in `mvr.lock` (or `$MVR_STATIC_OVERRIDES`) when compiling
+/// const SUIFRENS: &str = sui_mvr::mvr!("@suifrens/core");
+/// ```
+#[cfg(feature = "static-resolution")]
+pub use sui_mvr_macros::mvr;
+
+/// The reqwest version used by the resolver, for building clients passed to
+/// [`MvrResolver::with_http_client`]
+#[cfg(feature = "network")]
+pub use reqwest;
+
+// `std::time::Instant` panics on wasm32-unknown-unknown
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::Instant;
+
+/// Sleep on the native tokio timer or the browser timer on wasm32
+#[cfg(any(feature = "network", feature = "testing"))]
+pub(crate) async fn sleep(duration: std::time::Duration) {
+    #[cfg(not(target_arch = "wasm32"))]
+    tokio::time::sleep(duration).await;
+    #[cfg(target_arch = "wasm32")]
+    gloo_timers::future::sleep(duration).await;
+}
 
 /// Commonly used items for easy importing
 pub mod prelude {
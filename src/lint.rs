@@ -0,0 +1,447 @@
+//! Static checks for MVR names used in Rust source code
+//!
+//! Scans string literals that look like MVR names (`"@namespace/package"` or
+//! `"@namespace/package::module::Type"`), validates their format and cross-checks
+//! them against a resolver's static overrides, so typos surface before anything is
+//! resolved. Names are normalized and aliases expanded as they are for resolution.
+
+use crate::error::{validate_package_name, validate_type_name, MvrError, MvrResult};
+use crate::resolver::MvrResolver;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// An MVR name found in a string literal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NameUsage {
+    /// The literal's contents
+    pub name: String,
+    /// 1-based line where the literal starts
+    pub line: usize,
+    /// 1-based column where the literal starts
+    pub column: usize,
+}
+
+/// Problem found for a name usage
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LintIssueKind {
+    /// The name does not follow the MVR naming format
+    InvalidFormat { message: String },
+    /// The name is well-formed but not present in the overrides
+    NotOverridden { suggestion: Option<String> },
+}
+
+/// A name usage that failed linting
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintIssue {
+    pub file: PathBuf,
+    #[serde(flatten)]
+    pub usage: NameUsage,
+    #[serde(flatten)]
+    pub kind: LintIssueKind,
+}
+
+/// Result of linting a set of source files
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LintReport {
+    /// Number of source files scanned
+    pub files_scanned: usize,
+    /// Number of MVR names found
+    pub names_found: usize,
+    /// Names that failed validation or the override cross-check
+    pub issues: Vec<LintIssue>,
+}
+
+impl LintReport {
+    /// Check whether no issues were found
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Find string literals in Rust source that look like MVR names
+///
+/// Comments and character literals are skipped; normal and raw string literals
+/// are scanned. A literal counts as a name when it starts with `@`, contains a
+/// `/` and has no whitespace.
+pub fn scan_source(source: &str) -> Vec<NameUsage> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut usages = Vec::new();
+    let (mut line, mut column) = (1, 1);
+    let mut i = 0;
+
+    // Advance over `n` characters while tracking the position
+    let advance = |i: &mut usize, line: &mut usize, column: &mut usize, n: usize| {
+        for _ in 0..n {
+            if chars.get(*i) == Some(&'\n') {
+                *line += 1;
+                *column = 1;
+            } else {
+                *column += 1;
+            }
+            *i += 1;
+        }
+    };
+
+    while i < chars.len() {
+        let rest = &chars[i..];
+        match rest {
+            ['/', '/', ..] => {
+                let len = rest.iter().position(|&c| c == '\n').unwrap_or(rest.len());
+                advance(&mut i, &mut line, &mut column, len);
+            }
+            ['/', '*', ..] => {
+                let len = (2..rest.len())
+                    .find(|&j| rest[j - 1] == '*' && rest[j] == '/')
+                    .map_or(rest.len(), |j| j + 1);
+                advance(&mut i, &mut line, &mut column, len);
+            }
+            ['\'', '\\', ..] => {
+                let len = rest[2..]
+                    .iter()
+                    .position(|&c| c == '\'')
+                    .map_or(rest.len(), |j| j + 3);
+                advance(&mut i, &mut line, &mut column, len);
+            }
+            ['\'', _, '\'', ..] => advance(&mut i, &mut line, &mut column, 3),
+            ['r', '#' | '"', ..] if i == 0 || !is_ident_char(chars[i - 1]) => {
+                let hashes = rest[1..].iter().take_while(|&&c| c == '#').count();
+                if rest.get(1 + hashes) != Some(&'"') {
+                    advance(&mut i, &mut line, &mut column, 1);
+                    continue;
+                }
+
+                let (start_line, start_column) = (line, column);
+                let body_start = 2 + hashes;
+                let body_len = (body_start..rest.len())
+                    .find(|&j| {
+                        rest[j] == '"'
+                            && rest[j + 1..].iter().take_while(|&&c| c == '#').count() >= hashes
+                    })
+                    .map_or(rest.len() - body_start, |j| j - body_start);
+
+                let body: String = rest[body_start..body_start + body_len].iter().collect();
+                push_if_name(&mut usages, body, start_line, start_column);
+                let total = (body_start + body_len + 1 + hashes).min(rest.len());
+                advance(&mut i, &mut line, &mut column, total);
+            }
+            ['"', ..] => {
+                let (start_line, start_column) = (line, column);
+                let mut body = String::new();
+                let mut j = 1;
+                while j < rest.len() && rest[j] != '"' {
+                    if rest[j] == '\\' && j + 1 < rest.len() {
+                        body.push(rest[j + 1]);
+                        j += 2;
+                    } else {
+                        body.push(rest[j]);
+                        j += 1;
+                    }
+                }
+
+                push_if_name(&mut usages, body, start_line, start_column);
+                advance(&mut i, &mut line, &mut column, (j + 1).min(rest.len()));
+            }
+            _ => advance(&mut i, &mut line, &mut column, 1),
+        }
+    }
+
+    usages
+}
+
+/// Validate the names used in `source` and cross-check them against overrides
+///
+/// Names are looked up like [`MvrResolver::override_coverage`] does. When `resolver`
+/// is `None` or has no overrides, only the name format is checked.
+pub fn lint_source(
+    file: &Path,
+    source: &str,
+    resolver: Option<&MvrResolver>,
+) -> (usize, Vec<LintIssue>) {
+    let usages = scan_source(source);
+    let found = usages.len();
+
+    let issues = usages
+        .into_iter()
+        .filter_map(|usage| {
+            let kind = check_name(&usage.name, resolver)?;
+            Some(LintIssue {
+                file: file.to_path_buf(),
+                usage,
+                kind,
+            })
+        })
+        .collect();
+
+    (found, issues)
+}
+
+/// Lint every `.rs` file below `root`
+///
+/// Hidden directories and `target/` are skipped. Files are visited in sorted order
+/// so reports are stable.
+pub fn lint_workspace(root: &Path, resolver: Option<&MvrResolver>) -> MvrResult<LintReport> {
+    let mut files = Vec::new();
+    collect_rust_files(root, &mut files)?;
+    files.sort();
+
+    let mut report = LintReport::default();
+    for file in files {
+        let source = std::fs::read_to_string(&file).map_err(|e| {
+            MvrError::ConfigError(format!("failed to read '{}': {e}", file.display()))
+        })?;
+
+        let (found, issues) = lint_source(&file, &source, resolver);
+        report.files_scanned += 1;
+        report.names_found += found;
+        report.issues.extend(issues);
+    }
+
+    Ok(report)
+}
+
+fn collect_rust_files(path: &Path, files: &mut Vec<PathBuf>) -> MvrResult<()> {
+    if path.is_file() {
+        if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path.to_path_buf());
+        }
+        return Ok(());
+    }
+
+    let entries = std::fs::read_dir(path).map_err(|e| {
+        MvrError::ConfigError(format!(
+            "failed to read directory '{}': {e}",
+            path.display()
+        ))
+    })?;
+
+    for entry in entries.flatten() {
+        let entry_path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if entry_path.is_dir() && (name.starts_with('.') || name == "target") {
+            continue;
+        }
+        collect_rust_files(&entry_path, files)?;
+    }
+
+    Ok(())
+}
+
+fn check_name(name: &str, resolver: Option<&MvrResolver>) -> Option<LintIssueKind> {
+    let is_type = name.contains("::");
+    let normalized = match resolver {
+        Some(resolver) if is_type => resolver.normalize_type(name),
+        Some(resolver) => resolver.normalize_package(name),
+        None => name.into(),
+    };
+    let validation = if is_type {
+        validate_type_name(&normalized)
+    } else {
+        validate_package_name(&normalized)
+    };
+
+    if let Err(e) = validation {
+        return Some(LintIssueKind::InvalidFormat {
+            message: e.to_string(),
+        });
+    }
+
+    let resolver = resolver?;
+    let overrides = resolver.config().overrides.as_ref()?;
+    if resolver.override_coverage(&[name]).is_complete() {
+        return None;
+    }
+
+    let known = if is_type {
+        &overrides.types
+    } else {
+        &overrides.packages
+    };
+    let suggestion = closest_match(name, known.keys().map(String::as_str));
+    Some(LintIssueKind::NotOverridden { suggestion })
+}
+
+/// Pick the candidate closest to `name`, if it is close enough to be a likely typo
+pub(crate) fn closest_match<'a>(
+    name: &str,
+    candidates: impl Iterator<Item = &'a str>,
+) -> Option<String> {
+    closest_matches(name, candidates, 1).pop()
+}
+
+/// Up to `limit` distinct candidates close enough to `name` to be likely typos, closest first
+pub(crate) fn closest_matches<'a>(
+    name: &str,
+    candidates: impl Iterator<Item = &'a str>,
+    limit: usize,
+) -> Vec<String> {
+    let max_distance = (name.chars().count() / 4).max(1);
+
+    let mut matches: Vec<(usize, &str)> = candidates
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    matches.sort_unstable();
+    matches.dedup();
+    matches
+        .into_iter()
+        .take(limit)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+/// Levenshtein distance between two strings
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+fn push_if_name(usages: &mut Vec<NameUsage>, body: String, line: usize, column: usize) {
+    if body.starts_with('@') && body.contains('/') && !body.chars().any(char::is_whitespace) {
+        usages.push(NameUsage {
+            name: body,
+            line,
+            column,
+        });
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MvrOverrides;
+
+    #[test]
+    fn test_scan_source() {
+        let source = r##"
+// resolve_package("@commented/out")
+let a = resolver.resolve_package("@suifrens/core").await?;
+let b = "not a name";
+let c = '"'; let d = r#"@raw/name::module::Type"#;
+/* "@block/comment" */ let e = "@esc/name\"";
+"##;
+
+        let names: Vec<(String, usize, usize)> = scan_source(source)
+            .into_iter()
+            .map(|u| (u.name, u.line, u.column))
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                ("@suifrens/core".to_string(), 3, 34),
+                ("@raw/name::module::Type".to_string(), 5, 22),
+                ("@esc/name\"".to_string(), 6, 32),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_source_against_overrides() {
+        let overrides = MvrOverrides::new()
+            .with_package("@suifrens/core".to_string(), "0x1".to_string())
+            .with_type(
+                "@suifrens/core::suifren::SuiFren".to_string(),
+                "0x1::suifren::SuiFren".to_string(),
+            );
+
+        let source = r#"
+            resolver.resolve_package("@suifrens/core");
+            resolver.resolve_package("@suifens/core");
+            resolver.resolve_type("@suifrens/core::suifren::SuiFren");
+            resolver.resolve_package("@suifrens/");
+        "#;
+
+        let resolver = MvrResolver::testnet().with_overrides(overrides);
+        let (found, issues) = lint_source(Path::new("src/main.rs"), source, Some(&resolver));
+        assert_eq!(found, 4);
+        assert_eq!(issues.len(), 2);
+
+        assert_eq!(issues[0].usage.name, "@suifens/core");
+        assert_eq!(
+            issues[0].kind,
+            LintIssueKind::NotOverridden {
+                suggestion: Some("@suifrens/core".to_string())
+            }
+        );
+        assert!(matches!(
+            issues[1].kind,
+            LintIssueKind::InvalidFormat { .. }
+        ));
+
+        // Without overrides only the format is checked
+        let (_, issues) = lint_source(Path::new("src/main.rs"), source, None);
+        assert_eq!(issues.len(), 1);
+    }
+
+    #[test]
+    fn test_lint_source_normalizes_names() {
+        let resolver = MvrResolver::testnet()
+            .with_overrides(
+                MvrOverrides::new()
+                    .with_package("@MyOrg/core".to_string(), "0x1".to_string())
+                    .with_type(
+                        "@myorg/core::coin::COIN".to_string(),
+                        "0x1::coin::COIN".to_string(),
+                    ),
+            )
+            .with_alias("legacy", "@myorg/core");
+
+        let source = r#"
+            resolver.resolve_package("@myorg/core");
+            resolver.resolve_type("@MyOrg/Core::coin::COIN");
+            resolver.resolve_package("@MyOrg/cor");
+        "#;
+
+        let (found, issues) = lint_source(Path::new("src/main.rs"), source, Some(&resolver));
+        assert_eq!(found, 3);
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert_eq!(issues[0].usage.name, "@MyOrg/cor");
+        assert_eq!(
+            issues[0].kind,
+            LintIssueKind::NotOverridden {
+                suggestion: Some("@MyOrg/core".to_string())
+            }
+        );
+
+        // Aliases are expanded before the lookup
+        assert_eq!(check_name("legacy", Some(&resolver)), None);
+        assert_eq!(check_name("legacy::coin::COIN", Some(&resolver)), None);
+
+        // A resolver without overrides only checks the format
+        let (_, issues) = lint_source(
+            Path::new("src/main.rs"),
+            source,
+            Some(&MvrResolver::testnet()),
+        );
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("@suifrens/core", "@suifrens/core"), 0);
+        assert_eq!(edit_distance("@suifens/core", "@suifrens/core"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(
+            closest_match("@zzz/qqq", ["@suifrens/core"].into_iter()),
+            None
+        );
+    }
+}
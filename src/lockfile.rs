@@ -0,0 +1,176 @@
+//! `mvr.lock` files pinning resolved names for reproducible builds
+//!
+//! A lockfile records the address or type signature each name resolved to, along
+//! with the registry version it was resolved against. Committing it makes address
+//! changes show up as diffs in code review, and a resolver built with
+//! [`crate::MvrConfig::with_lockfile`] answers locked names without the registry.
+
+use crate::error::{MvrError, MvrResult};
+use crate::types::{MvrOverrides, Resolution};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Conventional lockfile name, also read by the `mvr!` macro
+pub const LOCKFILE_NAME: &str = "mvr.lock";
+
+/// Format version written by [`MvrLockfile::to_json`]
+pub const LOCKFILE_FORMAT: u32 = 1;
+
+/// A locked name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockEntry {
+    /// Package address or type signature
+    pub value: String,
+    /// Registry data version (ETag) the value was resolved against, if reported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl From<&Resolution> for LockEntry {
+    fn from(resolution: &Resolution) -> Self {
+        Self {
+            value: resolution.value.to_string(),
+            version: resolution.resolved_against_version.clone(),
+        }
+    }
+}
+
+/// Resolved packages and types pinned in an `mvr.lock` file
+///
+/// Entries are kept sorted so the file diffs cleanly.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MvrLockfile {
+    /// Lockfile format version
+    pub format: u32,
+    /// Locked package addresses by name
+    #[serde(default)]
+    pub packages: BTreeMap<String, LockEntry>,
+    /// Locked type signatures by name
+    #[serde(default)]
+    pub types: BTreeMap<String, LockEntry>,
+}
+
+impl MvrLockfile {
+    /// Create an empty lockfile
+    pub fn new() -> Self {
+        Self {
+            format: LOCKFILE_FORMAT,
+            ..Self::default()
+        }
+    }
+
+    /// Lock a package name to its resolution
+    pub fn with_package(mut self, name: String, resolution: &Resolution) -> Self {
+        self.packages.insert(name, resolution.into());
+        self
+    }
+
+    /// Lock a type name to its resolution
+    pub fn with_type(mut self, name: String, resolution: &Resolution) -> Self {
+        self.types.insert(name, resolution.into());
+        self
+    }
+
+    /// Parse a lockfile, rejecting formats newer than [`LOCKFILE_FORMAT`]
+    pub fn from_json(json: &str) -> MvrResult<Self> {
+        let lockfile: Self = serde_json::from_str(json)?;
+        if lockfile.format > LOCKFILE_FORMAT {
+            return Err(MvrError::ConfigError(format!(
+                "lockfile format {} is newer than supported format {LOCKFILE_FORMAT}",
+                lockfile.format
+            )));
+        }
+        Ok(lockfile)
+    }
+
+    /// Serialize the lockfile, with a trailing newline for friendlier diffs
+    pub fn to_json(&self) -> MvrResult<String> {
+        Ok(serde_json::to_string_pretty(self)? + "\n")
+    }
+
+    /// Read a lockfile from `path`
+    pub fn load(path: impl AsRef<Path>) -> MvrResult<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            MvrError::ConfigError(format!("failed to read lockfile '{}': {e}", path.display()))
+        })?;
+        Self::from_json(&json)
+    }
+
+    /// Write the lockfile to `path`
+    pub fn save(&self, path: impl AsRef<Path>) -> MvrResult<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_json()?).map_err(|e| {
+            MvrError::ConfigError(format!(
+                "failed to write lockfile '{}': {e}",
+                path.display()
+            ))
+        })
+    }
+
+    /// Locked values as static overrides
+    pub fn to_overrides(&self) -> MvrOverrides {
+        MvrOverrides {
+            packages: self
+                .packages
+                .iter()
+                .map(|(name, entry)| (name.clone(), entry.value.clone()))
+                .collect(),
+            types: self
+                .types
+                .iter()
+                .map(|(name, entry)| (name.clone(), entry.value.clone()))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ResolutionSource;
+
+    fn resolution(value: &str, version: Option<&str>) -> Resolution {
+        Resolution::new(
+            value.into(),
+            ResolutionSource::Network,
+            version.map(str::to_string),
+        )
+    }
+
+    #[test]
+    fn test_lockfile_round_trip() {
+        let lockfile = MvrLockfile::new()
+            .with_package("@b/pkg".to_string(), &resolution("0x2", Some("\"v7\"")))
+            .with_package("@a/pkg".to_string(), &resolution("0x1", None))
+            .with_type("@a/pkg::m::T".to_string(), &resolution("0x1::m::T", None));
+
+        let json = lockfile.to_json().unwrap();
+        assert!(json.find("@a/pkg").unwrap() < json.find("@b/pkg").unwrap());
+        assert!(json.ends_with('\n'));
+        assert_eq!(MvrLockfile::from_json(&json).unwrap(), lockfile);
+
+        let overrides = lockfile.to_overrides();
+        assert_eq!(overrides.packages["@b/pkg"], "0x2");
+        assert_eq!(overrides.types["@a/pkg::m::T"], "0x1::m::T");
+
+        let future = format!(r#"{{"format": {}}}"#, LOCKFILE_FORMAT + 1);
+        assert!(matches!(
+            MvrLockfile::from_json(&future),
+            Err(MvrError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_lockfile_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LOCKFILE_NAME);
+        let lockfile =
+            MvrLockfile::new().with_package("@a/pkg".to_string(), &resolution("0x1", None));
+
+        lockfile.save(&path).unwrap();
+        assert_eq!(MvrLockfile::load(&path).unwrap(), lockfile);
+        assert!(MvrLockfile::load(dir.path().join("missing.lock")).is_err());
+    }
+}
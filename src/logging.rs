@@ -0,0 +1,170 @@
+//! Structured JSON logs of resolution outcomes
+//!
+//! Enabled by the `tracing` feature. The resolver emits a debug event with target
+//! [`RESOLUTION_TARGET`] for every name it resolves, carrying these fields:
+//!
+//! - `name`: the package or type name
+//! - `network`: [`crate::MvrConfig::network_name`], `custom` when unset
+//! - `source`: `override`, `cache`, `network`, or `none` on failure
+//! - `duration_ms`: time spent resolving, for batches the whole batch so far
+//! - `status`: `ok`, or the [`crate::MvrError::code`] of the failure
+//!
+//! Any subscriber can consume these events. [`json_layer`] is an opt-in layer that
+//! writes them as one JSON object per line, for log pipelines that parse fields:
+//!
+//! ```rust
+//! use tracing_subscriber::layer::SubscriberExt;
+//!
+//! let subscriber = tracing_subscriber::registry().with(sui_mvr::logging::json_layer(std::io::stdout));
+//! tracing::subscriber::set_global_default(subscriber).unwrap();
+//! ```
+
+use serde_json::{Map, Value};
+use std::io::Write;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::{Context, Layer};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::SystemTime;
+#[cfg(target_arch = "wasm32")]
+use web_time::SystemTime;
+
+/// Target of the per-name resolution events
+pub const RESOLUTION_TARGET: &str = "sui_mvr::resolution";
+
+/// Layer writing resolution events as JSON lines, see [`json_layer`]
+pub struct JsonLogLayer<W> {
+    make_writer: W,
+}
+
+/// Write resolution events as JSON lines to writers made by `make_writer`
+///
+/// Each line holds `timestamp_ms` (Unix epoch), `level`, `message` and the event
+/// fields listed in the [module documentation](self). Other events are ignored, so
+/// the layer can sit next to a human-readable formatter.
+pub fn json_layer<W>(make_writer: W) -> JsonLogLayer<W>
+where
+    W: for<'w> MakeWriter<'w> + 'static,
+{
+    JsonLogLayer { make_writer }
+}
+
+impl<S, W> Layer<S> for JsonLogLayer<W>
+where
+    S: Subscriber,
+    W: for<'w> MakeWriter<'w> + 'static,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        if metadata.target() != RESOLUTION_TARGET {
+            return;
+        }
+
+        let timestamp_ms = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        let mut fields = Map::new();
+        fields.insert("timestamp_ms".to_string(), timestamp_ms.into());
+        fields.insert("level".to_string(), metadata.level().as_str().into());
+        event.record(&mut JsonVisitor(&mut fields));
+
+        let mut line = Value::Object(fields).to_string();
+        line.push('\n');
+        // Logging must never fail the resolution it reports on
+        let _ = self.make_writer.make_writer().write_all(line.as_bytes());
+    }
+}
+
+/// Collects event fields into a JSON object
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}").into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MvrConfig, MvrOverrides, MvrResolver};
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    /// Lines written through the layer
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_json_layer() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::registry().with(json_layer(move || writer.clone()));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let overrides =
+            MvrOverrides::new().with_package("@test/local".to_string(), "0x1".to_string());
+        let resolver = MvrResolver::new(
+            MvrConfig::localnet(9)
+                .with_network_name("devnet")
+                .with_overrides(overrides),
+        );
+        resolver.resolve_package("@test/local").await.unwrap();
+        assert!(resolver.resolve_package("invalid").await.is_err());
+        tracing::info!("unrelated");
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        for (line, (name, source, status)) in lines.iter().zip([
+            ("@test/local", "override", "ok"),
+            ("invalid", "none", "MVR_E_INVALID_PACKAGE_NAME"),
+        ]) {
+            assert_eq!(line["name"], name);
+            assert_eq!(line["network"], "devnet");
+            assert_eq!(line["source"], source);
+            assert_eq!(line["status"], status);
+            assert_eq!(line["level"], "DEBUG");
+            assert_eq!(line["message"], "resolution");
+            assert!(line["duration_ms"].as_f64().unwrap() >= 0.0);
+            assert!(line["timestamp_ms"].as_u64().unwrap() > 0);
+        }
+    }
+}
@@ -0,0 +1,279 @@
+//! Resolution counters kept inside the resolver
+//!
+//! Enabled by the `metrics` feature. Every package and type resolution is counted,
+//! along with where the answer came from and, for failures, the error code. Read the
+//! counters with [`crate::MvrResolver::metrics`] and export them to any monitoring
+//! system; clones of a resolver share the same counters. Resolution latencies are
+//! kept in log-linear histograms whose percentiles are accurate to within 12.5%.
+
+use crate::error::MvrError;
+use crate::types::ResolutionSource;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Snapshot of a resolver's counters
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ResolverMetrics {
+    /// Names resolved, successfully or not
+    pub requests: u64,
+    /// Names resolved successfully
+    pub successes: u64,
+    /// Names that failed to resolve
+    pub failures: u64,
+    /// Failures keyed by error code, see [`MvrError::code`]
+    pub failures_by_code: BTreeMap<String, u64>,
+    /// Successes answered by static overrides
+    pub override_hits: u64,
+    /// Successes answered by the cache
+    pub cache_hits: u64,
+    /// Successes answered by the registry
+    pub network_hits: u64,
+    /// Latency of successful resolutions
+    pub latency: ResolutionLatency,
+}
+
+/// Latency of successful resolutions by call shape and source
+///
+/// Override hits count as cached. A batch counts as a network resolution when any of
+/// its names had to be fetched from the registry.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ResolutionLatency {
+    /// Single-name resolutions answered by overrides or the cache
+    pub single_cached: LatencyPercentiles,
+    /// Single-name resolutions answered by the registry
+    pub single_network: LatencyPercentiles,
+    /// Batches answered entirely by overrides or the cache
+    pub batch_cached: LatencyPercentiles,
+    /// Batches that fetched names from the registry
+    pub batch_network: LatencyPercentiles,
+}
+
+/// Latency percentiles of one kind of resolution, zero before the first one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct LatencyPercentiles {
+    /// Resolutions recorded
+    pub count: u64,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+}
+
+impl ResolverMetrics {
+    /// Successes answered by `source`
+    pub fn source_count(&self, source: ResolutionSource) -> u64 {
+        match source {
+            ResolutionSource::Override => self.override_hits,
+            ResolutionSource::Cache => self.cache_hits,
+            ResolutionSource::Network => self.network_hits,
+        }
+    }
+
+    /// Share of requests that failed, 0.0 before the first request
+    pub fn failure_rate(&self) -> f64 {
+        if self.requests == 0 {
+            0.0
+        } else {
+            self.failures as f64 / self.requests as f64
+        }
+    }
+}
+
+/// Live counters behind [`ResolverMetrics`]
+#[derive(Debug, Default)]
+pub(crate) struct MetricsRecorder {
+    override_hits: AtomicU64,
+    cache_hits: AtomicU64,
+    network_hits: AtomicU64,
+    failures: Mutex<BTreeMap<&'static str, u64>>,
+    /// Indexed by [`LatencyKind`]
+    latency: [Histogram; 4],
+}
+
+/// Call shape and source a latency is recorded under
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum LatencyKind {
+    SingleCached,
+    SingleNetwork,
+    BatchCached,
+    BatchNetwork,
+}
+
+impl MetricsRecorder {
+    /// Count `count` names answered by `source`
+    pub(crate) fn record_success(&self, source: ResolutionSource, count: u64) {
+        let counter = match source {
+            ResolutionSource::Override => &self.override_hits,
+            ResolutionSource::Cache => &self.cache_hits,
+            ResolutionSource::Network => &self.network_hits,
+        };
+        counter.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Count `count` names that failed with `error`
+    pub(crate) fn record_failure(&self, error: &MvrError, count: u64) {
+        if count == 0 {
+            return;
+        }
+        let mut failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+        *failures.entry(error.code()).or_default() += count;
+    }
+
+    /// Record the latency of a successful resolution
+    pub(crate) fn record_latency(&self, kind: LatencyKind, elapsed: Duration) {
+        self.latency[kind as usize].record(elapsed);
+    }
+
+    pub(crate) fn snapshot(&self) -> ResolverMetrics {
+        let failures_by_code: BTreeMap<String, u64> = self
+            .failures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(code, count)| (code.to_string(), *count))
+            .collect();
+        let override_hits = self.override_hits.load(Ordering::Relaxed);
+        let cache_hits = self.cache_hits.load(Ordering::Relaxed);
+        let network_hits = self.network_hits.load(Ordering::Relaxed);
+        let successes = override_hits + cache_hits + network_hits;
+        let failures = failures_by_code.values().sum();
+        ResolverMetrics {
+            requests: successes + failures,
+            successes,
+            failures,
+            failures_by_code,
+            override_hits,
+            cache_hits,
+            network_hits,
+            latency: ResolutionLatency {
+                single_cached: self.latency[LatencyKind::SingleCached as usize].percentiles(),
+                single_network: self.latency[LatencyKind::SingleNetwork as usize].percentiles(),
+                batch_cached: self.latency[LatencyKind::BatchCached as usize].percentiles(),
+                batch_network: self.latency[LatencyKind::BatchNetwork as usize].percentiles(),
+            },
+        }
+    }
+}
+
+/// Sub-buckets per power of two, bounding the relative error of a bucket to 1/8
+const SUB_BUCKETS: u64 = 8;
+/// Latencies of 2^40µs (about 12 days) and more share the last bucket
+const MAX_EXPONENT: u32 = 40;
+const BUCKETS: usize = ((MAX_EXPONENT - 2) as u64 * SUB_BUCKETS) as usize;
+
+/// Lock-free log-linear histogram of microsecond latencies
+#[derive(Debug)]
+struct Histogram {
+    buckets: Box<[AtomicU64]>,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            buckets: (0..BUCKETS).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+}
+
+impl Histogram {
+    fn record(&self, elapsed: Duration) {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        self.buckets[bucket_index(micros)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn percentiles(&self) -> LatencyPercentiles {
+        let counts: Vec<u64> = self
+            .buckets
+            .iter()
+            .map(|bucket| bucket.load(Ordering::Relaxed))
+            .collect();
+        let count: u64 = counts.iter().sum();
+        let percentile = |p: u64| {
+            if count == 0 {
+                return Duration::ZERO;
+            }
+            // Rank of the sample at the percentile, rounded up
+            let rank = (count * p).div_ceil(100).max(1);
+            let mut seen = 0;
+            for (index, bucket) in counts.iter().enumerate() {
+                seen += bucket;
+                if seen >= rank {
+                    return Duration::from_micros(bucket_upper_bound(index));
+                }
+            }
+            Duration::from_micros(bucket_upper_bound(BUCKETS - 1))
+        };
+        LatencyPercentiles {
+            count,
+            p50: percentile(50),
+            p95: percentile(95),
+            p99: percentile(99),
+        }
+    }
+}
+
+/// Bucket of a latency: exact below 8µs, then 8 buckets per power of two
+fn bucket_index(micros: u64) -> usize {
+    if micros < SUB_BUCKETS {
+        return micros as usize;
+    }
+    let exponent = (63 - micros.leading_zeros()).min(MAX_EXPONENT - 1);
+    let sub_bucket = (micros >> (exponent - 3)).min(2 * SUB_BUCKETS - 1) - SUB_BUCKETS;
+    ((exponent as u64 - 2) * SUB_BUCKETS + sub_bucket) as usize
+}
+
+/// Largest latency in microseconds that falls in bucket `index`
+fn bucket_upper_bound(index: usize) -> u64 {
+    let index = index as u64;
+    if index < SUB_BUCKETS {
+        return index;
+    }
+    let exponent = index / SUB_BUCKETS + 2;
+    let sub_bucket = index % SUB_BUCKETS;
+    ((SUB_BUCKETS + sub_bucket + 1) << (exponent - 3)) - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_bounds() {
+        for micros in [0, 7, 8, 9, 15, 16, 17, 100, 1_000, 123_456, 10_000_000] {
+            let index = bucket_index(micros);
+            assert!(micros <= bucket_upper_bound(index), "{micros}");
+            assert!(
+                index == 0 || micros > bucket_upper_bound(index - 1),
+                "{micros}"
+            );
+            // Within 12.5% of the recorded value
+            assert!(bucket_upper_bound(index) - micros <= micros / 8, "{micros}");
+        }
+        assert_eq!(bucket_index(u64::MAX), BUCKETS - 1);
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let histogram = Histogram::default();
+        assert_eq!(histogram.percentiles(), LatencyPercentiles::default());
+
+        for millis in 1..=100 {
+            histogram.record(Duration::from_millis(millis));
+        }
+        let percentiles = histogram.percentiles();
+        assert_eq!(percentiles.count, 100);
+        for (value, expected) in [
+            (percentiles.p50, 50),
+            (percentiles.p95, 95),
+            (percentiles.p99, 99),
+        ] {
+            let expected = Duration::from_millis(expected);
+            assert!(
+                value >= expected && value <= expected + expected / 8,
+                "{value:?}"
+            );
+        }
+    }
+}
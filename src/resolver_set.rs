@@ -0,0 +1,221 @@
+//! One resolver per network for multi-network applications
+//!
+//! An [`MvrResolverSet`] builds its resolvers from a shared [`MvrConfig`] template, so
+//! cache, timeout and concurrency settings are configured once while each network
+//! gets its own registry endpoint, fullnode and cache.
+
+use crate::error::{MvrError, MvrResult};
+use crate::resolver::MvrResolver;
+use crate::types::MvrConfig;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// A Sui network served by an [`MvrResolverSet`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MvrNetwork {
+    Mainnet,
+    Testnet,
+    Devnet,
+    /// Any other network, e.g. a localnet, by name
+    Custom(String),
+}
+
+impl MvrNetwork {
+    /// Network name, as used in app records and the `?network=` query
+    pub fn name(&self) -> &str {
+        match self {
+            MvrNetwork::Mainnet => "mainnet",
+            MvrNetwork::Testnet => "testnet",
+            MvrNetwork::Devnet => "devnet",
+            MvrNetwork::Custom(name) => name,
+        }
+    }
+
+    /// Public fullnode of the network, `None` for custom networks
+    fn sui_rpc_url(&self) -> Option<String> {
+        match self {
+            MvrNetwork::Custom(_) => None,
+            network => Some(format!("https://fullnode.{}.sui.io:443", network.name())),
+        }
+    }
+}
+
+impl fmt::Display for MvrNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for MvrNetwork {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "mainnet" => MvrNetwork::Mainnet,
+            "testnet" => MvrNetwork::Testnet,
+            "devnet" => MvrNetwork::Devnet,
+            name => MvrNetwork::Custom(name.to_string()),
+        })
+    }
+}
+
+/// Resolvers for several networks built from one configuration template
+///
+/// ```rust
+/// use sui_mvr::{MvrConfig, MvrNetwork, MvrResolverSet};
+/// use std::time::Duration;
+///
+/// let resolvers = MvrResolverSet::new(MvrConfig::default().with_cache_ttl(Duration::from_secs(600)))
+///     .with_mainnet()
+///     .with_testnet();
+/// assert!(resolvers.get(&MvrNetwork::Mainnet).is_some());
+/// assert!(resolvers.get(&MvrNetwork::Devnet).is_none());
+/// ```
+#[derive(Clone)]
+pub struct MvrResolverSet {
+    template: MvrConfig,
+    resolvers: BTreeMap<MvrNetwork, MvrResolver>,
+}
+
+impl MvrResolverSet {
+    /// Create an empty set whose resolvers are configured like `template`
+    ///
+    /// The template's endpoint and fullnode URL are replaced per network; everything
+    /// else, including overrides, applies to every network.
+    pub fn new(template: MvrConfig) -> Self {
+        Self {
+            template,
+            resolvers: BTreeMap::new(),
+        }
+    }
+
+    /// Add mainnet, served by the public mainnet registry
+    pub fn with_mainnet(self) -> Self {
+        let endpoint_url = MvrConfig::mainnet().endpoint_url;
+        self.with_network(MvrNetwork::Mainnet, endpoint_url, None)
+    }
+
+    /// Add testnet, served by the public testnet registry
+    pub fn with_testnet(self) -> Self {
+        let endpoint_url = MvrConfig::testnet().endpoint_url;
+        self.with_network(MvrNetwork::Testnet, endpoint_url, None)
+    }
+
+    /// Add a network served by the registry at `endpoint_url`, replacing any existing one
+    ///
+    /// Without a `sui_rpc_url`, mainnet, testnet and devnet use their public fullnode
+    /// and custom networks the template's.
+    pub fn with_network(
+        mut self,
+        network: MvrNetwork,
+        endpoint_url: impl Into<String>,
+        sui_rpc_url: Option<String>,
+    ) -> Self {
+        let mut config = self
+            .template
+            .clone()
+            .with_endpoint(endpoint_url.into())
+            .with_network_name(network.name());
+        if let Some(sui_rpc_url) = sui_rpc_url.or_else(|| network.sui_rpc_url()) {
+            config = config.with_sui_rpc_url(sui_rpc_url);
+        }
+        self.resolvers.insert(network, MvrResolver::new(config));
+        self
+    }
+
+    /// Resolver for `network`, if it was added
+    pub fn get(&self, network: &MvrNetwork) -> Option<&MvrResolver> {
+        self.resolvers.get(network)
+    }
+
+    /// Resolver for `network`, failing with [`MvrError::ConfigError`] if it was not added
+    pub fn resolver(&self, network: &MvrNetwork) -> MvrResult<&MvrResolver> {
+        self.get(network).ok_or_else(|| {
+            MvrError::ConfigError(format!("no resolver configured for network '{network}'"))
+        })
+    }
+
+    /// Networks in the set
+    pub fn networks(&self) -> impl Iterator<Item = &MvrNetwork> {
+        self.resolvers.keys()
+    }
+
+    /// Networks and their resolvers
+    pub fn iter(&self) -> impl Iterator<Item = (&MvrNetwork, &MvrResolver)> {
+        self.resolvers.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MvrOverrides;
+    use std::time::Duration;
+
+    #[test]
+    fn test_network_names() {
+        for network in [
+            MvrNetwork::Mainnet,
+            MvrNetwork::Testnet,
+            MvrNetwork::Devnet,
+            MvrNetwork::Custom("localnet".to_string()),
+        ] {
+            assert_eq!(network.to_string().parse::<MvrNetwork>(), Ok(network));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolver_set() {
+        let overrides =
+            MvrOverrides::new().with_package("@suifrens/core".to_string(), "0x80d7".to_string());
+        let localnet = MvrNetwork::Custom("localnet".to_string());
+        let resolvers = MvrResolverSet::new(
+            MvrConfig::default()
+                .with_cache_ttl(Duration::from_secs(60))
+                .with_overrides(overrides),
+        )
+        .with_mainnet()
+        .with_network(MvrNetwork::Devnet, "https://devnet.example.com", None)
+        .with_network(localnet.clone(), "http://127.0.0.1:9", None);
+
+        assert_eq!(
+            resolvers.networks().collect::<Vec<_>>(),
+            [&MvrNetwork::Mainnet, &MvrNetwork::Devnet, &localnet]
+        );
+        let mainnet = resolvers.resolver(&MvrNetwork::Mainnet).unwrap();
+        assert_eq!(
+            mainnet.config().endpoint_url,
+            MvrConfig::mainnet().endpoint_url
+        );
+        assert_eq!(
+            mainnet.config().sui_rpc_url,
+            MvrConfig::mainnet().sui_rpc_url
+        );
+        assert_eq!(mainnet.config().cache_ttl, Duration::from_secs(60));
+        assert_eq!(mainnet.config().network_name.as_deref(), Some("mainnet"));
+
+        let devnet = resolvers.get(&MvrNetwork::Devnet).unwrap().config();
+        assert_eq!(devnet.endpoint_url, "https://devnet.example.com");
+        assert_eq!(
+            devnet.sui_rpc_url.as_deref(),
+            Some("https://fullnode.devnet.sui.io:443")
+        );
+        assert_eq!(
+            resolvers.get(&localnet).unwrap().config().sui_rpc_url,
+            MvrConfig::default().sui_rpc_url
+        );
+
+        // Overrides from the template apply on every network
+        for (_, resolver) in resolvers.iter() {
+            assert_eq!(
+                resolver.resolve_package("@suifrens/core").await.unwrap(),
+                "0x80d7"
+            );
+        }
+        assert!(matches!(
+            resolvers.resolver(&MvrNetwork::Testnet),
+            Err(MvrError::ConfigError(_))
+        ));
+    }
+}
@@ -0,0 +1,205 @@
+//! Detached ed25519 signatures for cache snapshots and lockfiles
+//!
+//! Enabled by the `signing` feature. Signing a file writes its signature next to it
+//! in `<file>.sig` (base64, one line), covering the file's exact bytes. Loading with
+//! a [`VerifyingKey`] reads both and fails with [`MvrError::ConfigError`] when the
+//! signature is missing or does not match, so a snapshot or `mvr.lock` edited after
+//! signing is never used.
+//!
+//! ```rust,no_run
+//! use sui_mvr::signing::SigningKey;
+//! use sui_mvr::MvrLockfile;
+//!
+//! # fn main() -> sui_mvr::error::MvrResult<()> {
+//! let key = SigningKey::from_bytes(&[7; 32]);
+//! MvrLockfile::new().save_signed("mvr.lock", &key)?;
+//! let lockfile = MvrLockfile::load_verified("mvr.lock", &key.verifying_key())?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{MvrError, MvrResult};
+use crate::lockfile::MvrLockfile;
+use crate::resolver::MvrResolver;
+use base64ct::{Base64, Encoding};
+use ed25519_dalek::{Signature, Signer};
+use std::path::{Path, PathBuf};
+
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+
+/// Path of the detached signature of `path`, `<path>.sig`
+pub fn signature_path(path: impl AsRef<Path>) -> PathBuf {
+    let mut signature = path.as_ref().as_os_str().to_owned();
+    signature.push(".sig");
+    PathBuf::from(signature)
+}
+
+/// Sign the current contents of `path`, writing the signature to [`signature_path`]
+pub fn sign_file(path: impl AsRef<Path>, key: &SigningKey) -> MvrResult<()> {
+    let path = path.as_ref();
+    let contents = std::fs::read(path)
+        .map_err(|e| MvrError::ConfigError(format!("failed to read '{}': {e}", path.display())))?;
+    let signature = key.sign(&contents);
+    let signature_path = signature_path(path);
+    std::fs::write(
+        &signature_path,
+        Base64::encode_string(&signature.to_bytes()) + "\n",
+    )
+    .map_err(|e| {
+        MvrError::ConfigError(format!(
+            "failed to write signature '{}': {e}",
+            signature_path.display()
+        ))
+    })
+}
+
+/// Read `path` after checking it against its detached signature
+pub fn read_verified(path: impl AsRef<Path>, key: &VerifyingKey) -> MvrResult<String> {
+    let path = path.as_ref();
+    let signature_path = signature_path(path);
+    let read_error = |file: &Path, e: std::io::Error| {
+        MvrError::ConfigError(format!("failed to read '{}': {e}", file.display()))
+    };
+    let contents = std::fs::read_to_string(path).map_err(|e| read_error(path, e))?;
+    let encoded =
+        std::fs::read_to_string(&signature_path).map_err(|e| read_error(&signature_path, e))?;
+
+    let invalid = || {
+        MvrError::ConfigError(format!(
+            "signature '{}' does not match '{}'",
+            signature_path.display(),
+            path.display()
+        ))
+    };
+    let bytes = Base64::decode_vec(encoded.trim()).map_err(|_| invalid())?;
+    let signature = Signature::from_slice(&bytes).map_err(|_| invalid())?;
+    key.verify_strict(contents.as_bytes(), &signature)
+        .map_err(|_| invalid())?;
+    Ok(contents)
+}
+
+impl MvrLockfile {
+    /// Write the lockfile to `path` and sign it, see [`sign_file`]
+    pub fn save_signed(&self, path: impl AsRef<Path>, key: &SigningKey) -> MvrResult<()> {
+        let path = path.as_ref();
+        self.save(path)?;
+        sign_file(path, key)
+    }
+
+    /// Read a lockfile from `path`, rejecting it unless its signature verifies
+    pub fn load_verified(path: impl AsRef<Path>, key: &VerifyingKey) -> MvrResult<Self> {
+        Self::from_json(&read_verified(path, key)?)
+    }
+}
+
+impl MvrResolver {
+    /// Write a cache snapshot and sign it, see [`MvrResolver::save_cache_snapshot`]
+    pub fn save_signed_cache_snapshot(
+        &self,
+        path: impl AsRef<Path>,
+        key: &SigningKey,
+    ) -> MvrResult<usize> {
+        let path = path.as_ref();
+        let saved = self.save_cache_snapshot(path)?;
+        sign_file(path, key)?;
+        Ok(saved)
+    }
+
+    /// Pre-populate the cache from a snapshot whose signature verifies
+    ///
+    /// Like [`MvrResolver::warm_cache_from_snapshot`], but nothing is loaded unless
+    /// the snapshot matches its detached signature.
+    pub fn warm_cache_from_verified_snapshot(
+        &self,
+        path: impl AsRef<Path>,
+        key: &VerifyingKey,
+    ) -> MvrResult<usize> {
+        self.warm_cache_from_snapshot_json(&read_verified(path, key)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{MvrConfig, MvrOverrides, Resolution, ResolutionSource};
+
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    #[test]
+    fn test_signed_lockfile() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mvr.lock");
+        let lockfile = MvrLockfile::new().with_package(
+            "@suifrens/core".to_string(),
+            &Resolution::new("0x1".into(), ResolutionSource::Network, None),
+        );
+
+        lockfile.save_signed(&path, &key(1)).unwrap();
+        assert!(signature_path(&path).ends_with("mvr.lock.sig"));
+        assert_eq!(
+            MvrLockfile::load_verified(&path, &key(1).verifying_key()).unwrap(),
+            lockfile
+        );
+        assert!(matches!(
+            MvrLockfile::load_verified(&path, &key(2).verifying_key()),
+            Err(MvrError::ConfigError(_))
+        ));
+
+        let tampered = std::fs::read_to_string(&path)
+            .unwrap()
+            .replace("0x1", "0x2");
+        std::fs::write(&path, tampered).unwrap();
+        assert!(matches!(
+            MvrLockfile::load_verified(&path, &key(1).verifying_key()),
+            Err(MvrError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_signed_cache_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snapshot.json");
+        let warm = MvrResolver::new(MvrConfig::localnet(9));
+        warm.warm_cache_from_snapshot_json(
+            &MvrOverrides::new()
+                .with_package("@suifrens/core".to_string(), "0x1".to_string())
+                .to_json()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(warm.save_signed_cache_snapshot(&path, &key(1)).unwrap(), 1);
+
+        let cold = MvrResolver::new(MvrConfig::localnet(9));
+        assert_eq!(
+            cold.warm_cache_from_verified_snapshot(&path, &key(1).verifying_key())
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            cold.resolve_package_cached("@suifrens/core").as_deref(),
+            Some("0x1")
+        );
+
+        std::fs::write(
+            &path,
+            MvrOverrides::new()
+                .with_package("@suifrens/core".to_string(), "0xbad".to_string())
+                .to_json()
+                .unwrap(),
+        )
+        .unwrap();
+        let tampered = MvrResolver::new(MvrConfig::localnet(9));
+        assert!(matches!(
+            tampered.warm_cache_from_verified_snapshot(&path, &key(1).verifying_key()),
+            Err(MvrError::ConfigError(_))
+        ));
+        assert!(tampered.cache_stats().unwrap().total_entries == 0);
+
+        std::fs::remove_file(signature_path(&path)).unwrap();
+        assert!(tampered
+            .warm_cache_from_verified_snapshot(&path, &key(1).verifying_key())
+            .is_err());
+    }
+}
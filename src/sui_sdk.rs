@@ -0,0 +1,1183 @@
+//! MVR-aware transaction building with `sui-sdk-types` and `sui-transaction-builder`
+//!
+//! Enabled by the `sui-rust-sdk` feature. Resolved names are returned as the SDK's
+//! [`Address`], [`TypeTag`] and [`Function`] types, and [`MvrResolver::add_move_call`]
+//! appends an MVR-named call to an existing [`TransactionBuilder`], also available as
+//! [`PtbMvrExt::mvr_move_call`] on the builder itself, while
+//! [`MvrResolver::resolve_addresses`] reports on a batch of names one by one. With the `network`
+//! feature, [`MvrResolver::dev_inspect_mvr_call`] runs a read-only call on the configured
+//! Sui fullnode and [`MvrResolver::estimate_gas`] recommends a gas budget for one.
+//! [`MvrResolver::execute_mvr_call`] builds, signs and submits a call with a
+//! caller-supplied [`TransactionSigner`]. [`build_sponsored`] finishes a builder into a
+//! transaction whose gas is paid by a sponsor.
+
+use crate::error::{validate_package_name, MvrError, MvrResult};
+#[cfg(feature = "network")]
+use crate::resolver::json_u64;
+use crate::resolver::{resolve_mvr_target_parts, MvrResolver};
+use std::collections::HashMap;
+use sui_sdk_types::MoveCall;
+#[cfg(feature = "network")]
+use sui_sdk_types::{
+    Command, Digest, GasPayment, ObjectReference, ProgrammableTransaction, TransactionExpiration,
+    TransactionKind,
+};
+
+pub use sui_sdk_types::{Address, Identifier, Input, Transaction, TypeTag, UserSignature};
+pub use sui_transaction_builder::{Argument, Function, ObjectInput, TransactionBuilder};
+
+/// Gas units added to the inspected computation cost (times the gas price) when
+/// recommending a budget, the same overhead the Sui TypeScript SDK uses
+#[cfg(feature = "network")]
+const GAS_SAFE_OVERHEAD: u64 = 1000;
+
+/// A value returned by a dev-inspected Move call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveReturnValue {
+    /// BCS encoding of the value
+    pub bcs: Vec<u8>,
+    /// Move type of the value
+    pub type_tag: TypeTag,
+}
+
+impl MoveReturnValue {
+    /// Decode the value from BCS into a matching Rust type
+    pub fn decode<T: serde::de::DeserializeOwned>(&self) -> MvrResult<T> {
+        bcs::from_bytes(&self.bcs).map_err(|e| {
+            MvrError::InvalidResponse(format!("cannot decode {} value: {e}", self.type_tag))
+        })
+    }
+}
+
+/// Signs transactions for [`MvrResolver::execute_mvr_call`]
+///
+/// The crate does not hold keys itself. Implement this over the key type of a signing
+/// crate, e.g. by delegating to `sui_crypto::SuiSigner::sign_transaction`.
+pub trait TransactionSigner {
+    /// Address the signer sends transactions from
+    fn address(&self) -> Address;
+
+    /// Sign the transaction data, including the intent prefix Sui expects
+    fn sign_transaction(&self, transaction: &Transaction) -> MvrResult<UserSignature>;
+}
+
+/// A transaction executed by [`MvrResolver::execute_transaction`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutedTransaction {
+    /// Transaction digest
+    pub digest: String,
+    /// Effects as reported by the fullnode (`status`, `gasUsed`, `created`, ...)
+    pub effects: serde_json::Value,
+}
+
+/// Outcome for one name of [`MvrResolver::resolve_addresses`]
+#[derive(Debug)]
+pub enum AddressResolution {
+    /// The name resolved to a well-formed address
+    Valid(Address),
+    /// The registry has no package under the name
+    NotFound,
+    /// The name itself is malformed, with the reason
+    InvalidName(MvrError),
+    /// The name resolved to a value that is not a valid address
+    InvalidAddress(String),
+}
+
+impl AddressResolution {
+    /// The address, if the name resolved to a valid one
+    pub fn address(&self) -> Option<Address> {
+        match self {
+            AddressResolution::Valid(address) => Some(*address),
+            _ => None,
+        }
+    }
+}
+
+/// Gas payment for a transaction whose gas is paid by a sponsor instead of the sender
+#[derive(Clone)]
+pub struct SponsoredGas {
+    /// Owner of the gas objects, who co-signs the transaction
+    pub sponsor: Address,
+    /// Gas coins owned by the sponsor
+    pub objects: Vec<ObjectInput>,
+    /// Gas budget, see [`MvrResolver::estimate_gas`]
+    pub budget: u64,
+    /// Gas price, usually the network's reference gas price
+    pub price: u64,
+}
+
+impl SponsoredGas {
+    /// Create a sponsored gas payment from the sponsor's gas coins
+    pub fn new(
+        sponsor: Address,
+        objects: impl IntoIterator<Item = ObjectInput>,
+        budget: u64,
+        price: u64,
+    ) -> Self {
+        Self {
+            sponsor,
+            objects: objects.into_iter().collect(),
+            budget,
+            price,
+        }
+    }
+}
+
+/// Finish `builder` into a transaction sent by `sender` with gas paid by a sponsor
+///
+/// The returned [`Transaction`] (the transaction data to sign) names `sender` as its
+/// sender and the sponsor as gas owner, so it must be signed by both. Calls can be
+/// added with [`MvrResolver::add_move_call`] beforehand. Fails with
+/// [`MvrError::InvalidArguments`] if the transaction is incomplete.
+pub fn build_sponsored(
+    mut builder: TransactionBuilder,
+    sender: Address,
+    gas: SponsoredGas,
+) -> MvrResult<Transaction> {
+    builder.set_sender(sender);
+    builder.set_sponsor(gas.sponsor);
+    builder.add_gas_objects(gas.objects);
+    builder.set_gas_budget(gas.budget);
+    builder.set_gas_price(gas.price);
+    builder
+        .try_build()
+        .map_err(|e| MvrError::InvalidArguments(format!("cannot build transaction: {e}")))
+}
+
+/// MVR-named calls on an existing [`TransactionBuilder`]
+///
+/// ```rust,ignore
+/// use sui_mvr::sui_sdk::PtbMvrExt;
+///
+/// let amount = ptb.pure(&1u64);
+/// let fren = ptb
+///     .mvr_move_call(&resolver, "@suifrens/core::suifren::mint", &[], vec![amount])
+///     .await?;
+/// ```
+pub trait PtbMvrExt {
+    /// Append a call to an MVR-named function, see [`MvrResolver::add_move_call`]
+    fn mvr_move_call(
+        &mut self,
+        resolver: &MvrResolver,
+        target: &str,
+        type_args: &[&str],
+        arguments: Vec<Argument>,
+    ) -> impl std::future::Future<Output = MvrResult<Argument>> + Send;
+}
+
+impl PtbMvrExt for TransactionBuilder {
+    fn mvr_move_call(
+        &mut self,
+        resolver: &MvrResolver,
+        target: &str,
+        type_args: &[&str],
+        arguments: Vec<Argument>,
+    ) -> impl std::future::Future<Output = MvrResult<Argument>> + Send {
+        resolver.add_move_call(self, target, type_args, arguments)
+    }
+}
+
+impl MvrResolver {
+    /// Resolve a package name to its on-chain [`Address`]
+    pub async fn resolve_address(&self, package_name: &str) -> MvrResult<Address> {
+        let address = self.resolve_package(package_name).await?;
+        address.parse().map_err(|e| {
+            MvrError::InvalidResponse(format!(
+                "'{package_name}' resolved to invalid address '{address}': {e}"
+            ))
+        })
+    }
+
+    /// Resolve a batch of package names to [`Address`]es, reporting on each name
+    ///
+    /// Unlike [`MvrResolver::resolve_packages`], a malformed name, a missing package or
+    /// a registry value that is not an address does not fail the batch: every name gets
+    /// an [`AddressResolution`], keyed by the name as given. Only failures of the lookup itself (network, rate limits, ...)
+    /// are returned as errors.
+    pub async fn resolve_addresses(
+        &self,
+        package_names: &[&str],
+    ) -> MvrResult<HashMap<String, AddressResolution>> {
+        let mut report = HashMap::with_capacity(package_names.len());
+        let mut valid = Vec::with_capacity(package_names.len());
+        for &name in package_names {
+            match validate_package_name(&self.normalize_package(name)) {
+                Ok(()) => valid.push(name),
+                Err(e) => {
+                    report.insert(name.to_string(), AddressResolution::InvalidName(e));
+                }
+            }
+        }
+
+        let mut addresses = self.resolve_package_values(&valid, false).await?;
+        for name in valid {
+            let resolution = match addresses.remove(name) {
+                None => AddressResolution::NotFound,
+                Some(address) => match address.parse() {
+                    Ok(address) => AddressResolution::Valid(address),
+                    Err(_) => AddressResolution::InvalidAddress(address),
+                },
+            };
+            report.insert(name.to_string(), resolution);
+        }
+        Ok(report)
+    }
+
+    /// Resolve a type to a [`TypeTag`]
+    ///
+    /// Names starting with `@` are resolved through the registry, anything else is
+    /// parsed as a type tag as is (`u64`, `0x2::sui::SUI`, ...).
+    pub async fn resolve_type_tag(&self, type_name: &str) -> MvrResult<TypeTag> {
+        let type_tag = if type_name.starts_with('@') {
+            self.resolve_type(type_name).await?
+        } else {
+            type_name.to_string()
+        };
+        type_tag
+            .parse()
+            .map_err(|_| MvrError::InvalidTypeName(type_tag))
+    }
+
+    /// Resolve a `@namespace/package::module::function` target to a [`Function`]
+    ///
+    /// Type arguments may be embedded in the target (`...::swap<@tokens/usdc::usdc::USDC>`)
+    /// or passed in `type_args`, each of which is resolved with
+    /// [`MvrResolver::resolve_type_tag`].
+    pub async fn resolve_move_function(
+        &self,
+        target: &str,
+        type_args: &[&str],
+    ) -> MvrResult<Function> {
+        let call = self.resolve_call(target, type_args).await?;
+        Ok(Function::new(call.package, call.module, call.function)
+            .with_type_args(call.type_arguments))
+    }
+
+    /// Append a call to an MVR-named function to `builder`
+    ///
+    /// Returns the call's result [`Argument`], like [`TransactionBuilder::move_call`].
+    pub async fn add_move_call(
+        &self,
+        builder: &mut TransactionBuilder,
+        target: &str,
+        type_args: &[&str],
+        arguments: Vec<Argument>,
+    ) -> MvrResult<Argument> {
+        let function = self.resolve_move_function(target, type_args).await?;
+        Ok(builder.move_call(function, arguments))
+    }
+
+    /// Run an MVR-named function read-only and return its results
+    ///
+    /// The call is built as a single-command programmable transaction taking `inputs`
+    /// as its arguments, in order, and dev-inspected as `sender` on the configured Sui
+    /// fullnode ([`crate::MvrConfig::with_sui_rpc_url`]), so nothing is signed or
+    /// executed. Returns the function's return values in order, see
+    /// [`MoveReturnValue::decode`]. A call that aborts is reported as
+    /// [`MvrError::InvalidResponse`].
+    #[cfg(feature = "network")]
+    pub async fn dev_inspect_mvr_call(
+        &self,
+        sender: Address,
+        target: &str,
+        type_args: &[&str],
+        inputs: Vec<Input>,
+    ) -> MvrResult<Vec<MoveReturnValue>> {
+        let inspected = self.dev_inspect(sender, target, type_args, inputs).await?;
+        let return_values: Vec<(Vec<u8>, String)> =
+            match inspected.pointer("/results/0/returnValues") {
+                Some(values) => serde_json::from_value(values.clone())?,
+                None => Vec::new(),
+            };
+
+        return_values
+            .into_iter()
+            .map(|(bcs, type_tag)| {
+                let type_tag = type_tag.parse().map_err(|_| {
+                    MvrError::InvalidResponse(format!("invalid return type '{type_tag}'"))
+                })?;
+                Ok(MoveReturnValue { bcs, type_tag })
+            })
+            .collect()
+    }
+
+    /// Recommend a gas budget for calling an MVR-named function as `sender`
+    ///
+    /// The call is built as a single-command programmable transaction taking `inputs`
+    /// as its arguments, in order, and dev-inspected on the configured Sui fullnode
+    /// ([`crate::MvrConfig::with_sui_rpc_url`]), like [`MvrResolver::dev_inspect_mvr_call`].
+    /// The budget covers the inspected computation cost plus a safety overhead, and the
+    /// net storage cost when positive. A call that aborts is reported as
+    /// [`MvrError::InvalidResponse`].
+    #[cfg(feature = "network")]
+    pub async fn estimate_gas(
+        &self,
+        sender: Address,
+        target: &str,
+        type_args: &[&str],
+        inputs: Vec<Input>,
+    ) -> MvrResult<u64> {
+        let (budget, _) = self.gas_estimate(sender, target, type_args, inputs).await?;
+        Ok(budget)
+    }
+
+    /// Call an MVR-named function as the signer and wait for its effects
+    ///
+    /// The call is built as a single-command programmable transaction taking `inputs`
+    /// as its arguments, in order. The gas budget comes from
+    /// [`MvrResolver::estimate_gas`] at the reference gas price, and is paid from the
+    /// signer's SUI coins on the configured Sui fullnode
+    /// ([`crate::MvrConfig::with_sui_rpc_url`]). Not enough SUI fails with
+    /// [`MvrError::InvalidArguments`] before anything is signed. The signed transaction
+    /// is then submitted with [`MvrResolver::execute_transaction`].
+    #[cfg(feature = "network")]
+    pub async fn execute_mvr_call(
+        &self,
+        signer: &impl TransactionSigner,
+        target: &str,
+        type_args: &[&str],
+        inputs: Vec<Input>,
+    ) -> MvrResult<ExecutedTransaction> {
+        let sender = signer.address();
+        let (budget, price) = self
+            .gas_estimate(sender, target, type_args, inputs.clone())
+            .await?;
+        let objects = self.gas_coins(sender, budget).await?;
+        let transaction = Transaction {
+            kind: self.single_call(target, type_args, inputs).await?,
+            sender,
+            gas_payment: GasPayment {
+                objects,
+                owner: sender,
+                price,
+                budget,
+            },
+            expiration: TransactionExpiration::None,
+        };
+        let signature = signer.sign_transaction(&transaction)?;
+        self.execute_transaction(&transaction, &[signature]).await
+    }
+
+    /// Submit a signed transaction and wait until the fullnode has executed it
+    ///
+    /// `signatures` holds one signature per required signer: the sender's, plus the
+    /// sponsor's for transactions from [`build_sponsored`]. A transaction that was
+    /// executed but failed, e.g. because the call aborted, is reported as
+    /// [`MvrError::InvalidResponse`] with its digest.
+    #[cfg(feature = "network")]
+    pub async fn execute_transaction(
+        &self,
+        transaction: &Transaction,
+        signatures: &[UserSignature],
+    ) -> MvrResult<ExecutedTransaction> {
+        use base64ct::{Base64, Encoding};
+
+        let bytes = bcs::to_bytes(transaction).map_err(|e| {
+            MvrError::InvalidArguments(format!("cannot serialize transaction: {e}"))
+        })?;
+        let signatures: Vec<String> = signatures.iter().map(UserSignature::to_base64).collect();
+        let mut result = self
+            .sui_rpc(
+                "sui_executeTransactionBlock",
+                serde_json::json!([
+                    Base64::encode_string(&bytes),
+                    signatures,
+                    {"showEffects": true},
+                    "WaitForLocalExecution",
+                ]),
+            )
+            .await?;
+
+        let digest = result
+            .get("digest")
+            .and_then(|d| d.as_str())
+            .ok_or_else(|| MvrError::InvalidResponse("digest not found in response".to_string()))?
+            .to_string();
+        let effects = result
+            .get_mut("effects")
+            .map(serde_json::Value::take)
+            .ok_or_else(|| {
+                MvrError::InvalidResponse(format!("effects of transaction {digest} not found"))
+            })?;
+        if effects.pointer("/status/status").and_then(|s| s.as_str()) != Some("success") {
+            let error = effects
+                .pointer("/status/error")
+                .and_then(|e| e.as_str())
+                .unwrap_or("unknown error");
+            return Err(MvrError::InvalidResponse(format!(
+                "transaction {digest} failed: {error}"
+            )));
+        }
+        Ok(ExecutedTransaction { digest, effects })
+    }
+
+    /// Recommended gas budget for a call, with the reference gas price it assumes
+    #[cfg(feature = "network")]
+    async fn gas_estimate(
+        &self,
+        sender: Address,
+        target: &str,
+        type_args: &[&str],
+        inputs: Vec<Input>,
+    ) -> MvrResult<(u64, u64)> {
+        let (inspected, gas_price) = futures::future::try_join(
+            self.dev_inspect(sender, target, type_args, inputs),
+            self.reference_gas_price(),
+        )
+        .await?;
+
+        let gas_used = inspected.pointer("/effects/gasUsed").ok_or_else(|| {
+            MvrError::InvalidResponse("gasUsed not found in response".to_string())
+        })?;
+        let cost = |field: &str| {
+            gas_used
+                .get(field)
+                .and_then(json_u64)
+                .ok_or_else(|| MvrError::InvalidResponse(format!("invalid gasUsed.{field}")))
+        };
+        let computation = cost("computationCost")? + GAS_SAFE_OVERHEAD * gas_price;
+        let storage = cost("storageCost")?;
+        let rebate = cost("storageRebate")?;
+        let budget = computation.max((computation + storage).saturating_sub(rebate));
+        Ok((budget, gas_price))
+    }
+
+    /// SUI coins of `owner` covering `budget`, largest first
+    #[cfg(feature = "network")]
+    async fn gas_coins(&self, owner: Address, budget: u64) -> MvrResult<Vec<ObjectReference>> {
+        let coins = self
+            .sui_rpc(
+                "suix_getCoins",
+                serde_json::json!([owner.to_string(), "0x2::sui::SUI"]),
+            )
+            .await?;
+        let invalid = || MvrError::InvalidResponse(format!("invalid coin list {coins}"));
+        let mut coins = coins
+            .get("data")
+            .and_then(|data| data.as_array())
+            .ok_or_else(invalid)?
+            .iter()
+            .map(|coin| {
+                let field = |name: &str| coin.get(name).and_then(|v| v.as_str());
+                let reference = ObjectReference::new(
+                    field("coinObjectId").and_then(|id| id.parse().ok())?,
+                    coin.get("version").and_then(json_u64)?,
+                    field("digest").and_then(|d| d.parse::<Digest>().ok())?,
+                );
+                Some((coin.get("balance").and_then(json_u64)?, reference))
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(invalid)?;
+        coins.sort_by_key(|(balance, _)| std::cmp::Reverse(*balance));
+
+        let mut total = 0u64;
+        let mut objects = Vec::new();
+        for (balance, reference) in coins {
+            if total >= budget {
+                break;
+            }
+            total = total.saturating_add(balance);
+            objects.push(reference);
+        }
+        if total < budget {
+            return Err(MvrError::InvalidArguments(format!(
+                "{owner} has {total} MIST in SUI coins, less than the gas budget of {budget}"
+            )));
+        }
+        Ok(objects)
+    }
+
+    /// Resolve a target and its type arguments to a [`MoveCall`] without arguments
+    ///
+    /// Type arguments embedded in `target` come before `type_args`.
+    async fn resolve_call(&self, target: &str, type_args: &[&str]) -> MvrResult<MoveCall> {
+        let parts = resolve_mvr_target_parts(self, target).await?;
+        let identifier = |name: &str| {
+            Identifier::new(name).map_err(|_| {
+                MvrError::invalid_target(
+                    target.to_string(),
+                    format!("'{name}' is not a Move identifier"),
+                )
+            })
+        };
+        let module = identifier(&parts.module)?;
+        let function = identifier(&parts.function)?;
+        let package = parts.package.parse().map_err(|e| {
+            MvrError::InvalidResponse(format!(
+                "'{target}' resolved to invalid address '{}': {e}",
+                parts.package
+            ))
+        })?;
+
+        let mut type_arguments = parts
+            .type_args
+            .iter()
+            .map(|t| t.parse().map_err(|_| MvrError::InvalidTypeName(t.clone())))
+            .collect::<MvrResult<Vec<TypeTag>>>()?;
+        type_arguments.extend(
+            futures::future::try_join_all(type_args.iter().map(|t| self.resolve_type_tag(t)))
+                .await?,
+        );
+        Ok(MoveCall {
+            package,
+            module,
+            function,
+            type_arguments,
+            arguments: Vec::new(),
+        })
+    }
+
+    /// A transaction calling `target` once, with `inputs` as its arguments in order
+    #[cfg(feature = "network")]
+    async fn single_call(
+        &self,
+        target: &str,
+        type_args: &[&str],
+        inputs: Vec<Input>,
+    ) -> MvrResult<TransactionKind> {
+        let mut call = self.resolve_call(target, type_args).await?;
+        call.arguments = (0..inputs.len() as u16)
+            .map(sui_sdk_types::Argument::Input)
+            .collect();
+        Ok(TransactionKind::ProgrammableTransaction(
+            ProgrammableTransaction {
+                inputs,
+                commands: vec![Command::MoveCall(call)],
+            },
+        ))
+    }
+
+    /// Dev-inspect a single call to `target` and return the fullnode's results
+    #[cfg(feature = "network")]
+    async fn dev_inspect(
+        &self,
+        sender: Address,
+        target: &str,
+        type_args: &[&str],
+        inputs: Vec<Input>,
+    ) -> MvrResult<serde_json::Value> {
+        use base64ct::{Base64, Encoding};
+
+        let kind = self.single_call(target, type_args, inputs).await?;
+        let bytes = bcs::to_bytes(&kind)
+            .map_err(|e| MvrError::InvalidArguments(format!("cannot serialize {target}: {e}")))?;
+
+        let result = self
+            .sui_rpc(
+                "sui_devInspectTransactionBlock",
+                serde_json::json!([sender.to_string(), Base64::encode_string(&bytes)]),
+            )
+            .await?;
+        if let Some(error) = result.get("error").and_then(|e| e.as_str()) {
+            return Err(MvrError::InvalidResponse(format!(
+                "dev-inspect of {target} failed: {error}"
+            )));
+        }
+        Ok(result)
+    }
+
+    /// The network's current reference gas price
+    #[cfg(feature = "network")]
+    async fn reference_gas_price(&self) -> MvrResult<u64> {
+        let price = self
+            .sui_rpc("suix_getReferenceGasPrice", serde_json::json!([]))
+            .await?;
+        json_u64(&price).ok_or_else(|| {
+            MvrError::InvalidResponse(format!("invalid reference gas price {price}"))
+        })
+    }
+}
+
+/// Fixtures shared by the tests of the `sui-rust-sdk` modules
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+    use crate::types::{MvrConfig, MvrOverrides};
+
+    /// Resolver with overrides for `@suifrens/core`, its `SuiFren` type and a package
+    /// pointing at a malformed address
+    pub(crate) fn resolver() -> MvrResolver {
+        let overrides = MvrOverrides::new()
+            .with_package("@suifrens/core".to_string(), "0x80d7".to_string())
+            .with_package("@bad/pkg".to_string(), "not-an-address".to_string())
+            .with_type(
+                "@suifrens/core::suifren::SuiFren".to_string(),
+                "0x80d7::suifren::SuiFren".to_string(),
+            );
+        MvrResolver::new(MvrConfig::localnet(9).with_overrides(overrides))
+    }
+
+    /// Finish `builder` into a transaction so two builders can be compared
+    pub(crate) fn build(mut builder: TransactionBuilder) -> Transaction {
+        builder.set_sender(Address::ZERO);
+        builder.set_gas_budget(1_000_000);
+        builder.set_gas_price(1000);
+        builder.add_gas_objects([ObjectInput::owned(
+            Address::ZERO,
+            1,
+            sui_sdk_types::Digest::ZERO,
+        )]);
+        builder.try_build().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::{build, resolver};
+    use super::*;
+    #[cfg(feature = "network")]
+    use crate::types::{MvrConfig, MvrOverrides};
+
+    #[tokio::test]
+    async fn test_resolve_sdk_types() {
+        let resolver = resolver();
+        assert_eq!(
+            resolver.resolve_address("@suifrens/core").await.unwrap(),
+            Address::from_static("0x80d7")
+        );
+        assert!(matches!(
+            resolver.resolve_address("@bad/pkg").await,
+            Err(MvrError::InvalidResponse(_))
+        ));
+
+        let tag = resolver
+            .resolve_type_tag("@suifrens/core::suifren::SuiFren")
+            .await
+            .unwrap();
+        assert_eq!(tag, "0x80d7::suifren::SuiFren".parse().unwrap());
+        assert_eq!(
+            resolver.resolve_type_tag("u64").await.unwrap(),
+            TypeTag::U64
+        );
+        assert!(matches!(
+            resolver.resolve_type_tag("not a type").await,
+            Err(MvrError::InvalidTypeName(_))
+        ));
+    }
+
+    #[cfg(feature = "network")]
+    #[tokio::test]
+    async fn test_resolve_addresses() {
+        let mut server = mockito::Server::new_async().await;
+        let _missing = server
+            .mock("POST", "/v1/resolution/bulk")
+            .with_status(200)
+            .with_body(r#"{"resolution": {}}"#)
+            .create_async()
+            .await;
+        let overrides = MvrOverrides::new()
+            .with_package("@suifrens/core".to_string(), "0x80d7".to_string())
+            .with_package("@bad/pkg".to_string(), "not-an-address".to_string());
+        let resolver = MvrResolver::new(
+            MvrConfig::default()
+                .with_endpoint(server.url())
+                .with_overrides(overrides),
+        );
+
+        let report = resolver
+            .resolve_addresses(&["@suifrens/core", "@bad/pkg", "@nobody/pkg", "no-at-sign"])
+            .await
+            .unwrap();
+        assert_eq!(report.len(), 4);
+        assert_eq!(
+            report["@suifrens/core"].address(),
+            Some(Address::from_static("0x80d7"))
+        );
+        assert!(matches!(
+            &report["@bad/pkg"],
+            AddressResolution::InvalidAddress(address) if address == "not-an-address"
+        ));
+        assert!(matches!(report["@nobody/pkg"], AddressResolution::NotFound));
+        assert!(matches!(
+            report["no-at-sign"],
+            AddressResolution::InvalidName(MvrError::InvalidPackageName(_))
+        ));
+    }
+
+    #[cfg(feature = "network")]
+    #[tokio::test]
+    async fn test_resolve_addresses_bad_registry_value() {
+        let mut server = mockito::Server::new_async().await;
+        let bulk = server
+            .mock("POST", "/v1/resolution/bulk")
+            .with_status(200)
+            .with_body(
+                r#"{"resolution": {
+                    "@good/pkg": {"package_id": "0x80d7"},
+                    "@broken/pkg": {"package_id": "0xnot-hex"}
+                }}"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+        let resolver = MvrResolver::new(MvrConfig::default().with_endpoint(server.url()));
+
+        let report = resolver
+            .resolve_addresses(&["@good/pkg", "@broken/pkg"])
+            .await
+            .unwrap();
+        assert_eq!(
+            report["@good/pkg"].address(),
+            Some(Address::from_static("0x80d7"))
+        );
+        assert!(matches!(
+            &report["@broken/pkg"],
+            AddressResolution::InvalidAddress(address) if address == "0xnot-hex"
+        ));
+
+        // The bad value is not cached, and still fails a plain batch resolution
+        assert!(resolver.resolve_package_cached("@broken/pkg").is_none());
+        assert!(matches!(
+            resolver.resolve_packages(&["@broken/pkg"]).await,
+            Err(MvrError::MalformedResponse(_))
+        ));
+        bulk.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_add_move_call() {
+        let resolver = resolver();
+        let expected = Function::new(
+            Address::from_static("0x80d7"),
+            Identifier::from_static("suifren"),
+            Identifier::from_static("mint"),
+        )
+        .with_type_args(vec!["0x80d7::suifren::SuiFren".parse().unwrap()]);
+
+        let mut resolved = TransactionBuilder::new();
+        let amount = resolved.pure(&1u64);
+        resolver
+            .add_move_call(
+                &mut resolved,
+                "@suifrens/core::suifren::mint",
+                &["@suifrens/core::suifren::SuiFren"],
+                vec![amount],
+            )
+            .await
+            .unwrap();
+
+        let mut direct = TransactionBuilder::new();
+        let amount = direct.pure(&1u64);
+        direct.move_call(expected, vec![amount]);
+
+        let mut embedded = TransactionBuilder::new();
+        let amount = embedded.pure(&1u64);
+        resolver
+            .add_move_call(
+                &mut embedded,
+                "@suifrens/core::suifren::mint<@suifrens/core::suifren::SuiFren>",
+                &[],
+                vec![amount],
+            )
+            .await
+            .unwrap();
+
+        let direct = build(direct);
+        assert_eq!(build(resolved), direct);
+        assert_eq!(build(embedded), direct);
+        let error = resolver
+            .resolve_move_function("@suifrens/core::suifren::bad-name", &[])
+            .await
+            .err()
+            .unwrap();
+        assert!(
+            matches!(&error, MvrError::InvalidTarget { reason, .. } if reason == "'bad-name' is not a Move identifier"),
+            "{error}"
+        );
+        assert_eq!(error.code(), "MVR_E_INVALID_TARGET");
+        assert_eq!(error.name_error(), None);
+        assert!(!error.to_string().contains("illegal character"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn test_ptb_mvr_ext() {
+        let resolver = resolver();
+        let mut ptb = TransactionBuilder::new();
+        let amount = ptb.pure(&1u64);
+        let fren = ptb
+            .mvr_move_call(
+                &resolver,
+                "@suifrens/core::suifren::mint",
+                &["@suifrens/core::suifren::SuiFren"],
+                vec![amount],
+            )
+            .await
+            .unwrap();
+        ptb.mvr_move_call(&resolver, "@suifrens/core::suifren::burn", &[], vec![fren])
+            .await
+            .unwrap();
+
+        let function = |name: &'static str| {
+            Function::new(
+                Address::from_static("0x80d7"),
+                Identifier::from_static("suifren"),
+                Identifier::from_static(name),
+            )
+        };
+        let mut direct = TransactionBuilder::new();
+        let amount = direct.pure(&1u64);
+        let fren = direct.move_call(
+            function("mint").with_type_args(vec!["0x80d7::suifren::SuiFren".parse().unwrap()]),
+            vec![amount],
+        );
+        direct.move_call(function("burn"), vec![fren]);
+        assert_eq!(build(ptb), build(direct));
+
+        let mut ptb = TransactionBuilder::new();
+        assert!(matches!(
+            ptb.mvr_move_call(
+                &resolver,
+                "@suifrens/core::suifren::mint",
+                &["not a type"],
+                vec![]
+            )
+            .await,
+            Err(MvrError::InvalidTypeName(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_build_sponsored() {
+        let resolver = resolver();
+        let user = Address::from_static("0x1");
+        let relayer = Address::from_static("0x2");
+        let gas = SponsoredGas::new(
+            relayer,
+            [ObjectInput::owned(
+                Address::from_static("0x3"),
+                1,
+                sui_sdk_types::Digest::ZERO,
+            )],
+            1_000_000,
+            1000,
+        );
+
+        let mut builder = TransactionBuilder::new();
+        resolver
+            .add_move_call(&mut builder, "@suifrens/core::suifren::mint", &[], vec![])
+            .await
+            .unwrap();
+        let transaction = build_sponsored(builder, user, gas.clone()).unwrap();
+        assert_eq!(transaction.sender, user);
+        assert_eq!(transaction.gas_payment.owner, relayer);
+        assert_eq!(transaction.gas_payment.budget, 1_000_000);
+        assert_eq!(transaction.gas_payment.objects.len(), 1);
+
+        let no_gas = SponsoredGas::new(relayer, [], 1_000_000, 1000);
+        assert!(matches!(
+            build_sponsored(TransactionBuilder::new(), user, no_gas),
+            Err(MvrError::InvalidArguments(_))
+        ));
+    }
+
+    #[cfg(feature = "network")]
+    #[tokio::test]
+    async fn test_estimate_gas() {
+        use base64ct::{Base64, Encoding};
+        use mockito::Matcher;
+
+        let mut server = mockito::Server::new_async().await;
+        let amount = Input::Pure(bcs::to_bytes(&1u64).unwrap());
+        let expected = TransactionKind::ProgrammableTransaction(ProgrammableTransaction {
+            inputs: vec![amount.clone()],
+            commands: vec![Command::MoveCall(MoveCall {
+                package: Address::from_static("0x80d7"),
+                module: Identifier::from_static("suifren"),
+                function: Identifier::from_static("mint"),
+                type_arguments: vec![],
+                arguments: vec![sui_sdk_types::Argument::Input(0)],
+            })],
+        });
+        let tx_bytes = Base64::encode_string(&bcs::to_bytes(&expected).unwrap());
+        let inspect = server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "method": "sui_devInspectTransactionBlock",
+                "params": [Address::ZERO.to_string(), tx_bytes],
+            })))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": {
+                    "effects": {"gasUsed": {
+                        "computationCost": "1000000",
+                        "storageCost": "2000000",
+                        "storageRebate": "500000",
+                    }},
+                    "results": [],
+                }})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let gas_price = server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJson(
+                serde_json::json!({"method": "suix_getReferenceGasPrice"}),
+            ))
+            .with_status(200)
+            .with_body(r#"{"jsonrpc": "2.0", "id": 1, "result": "750"}"#)
+            .create_async()
+            .await;
+
+        let overrides =
+            MvrOverrides::new().with_package("@suifrens/core".to_string(), "0x80d7".to_string());
+        let resolver = MvrResolver::new(
+            MvrConfig::localnet(9)
+                .with_overrides(overrides)
+                .with_sui_rpc_url(server.url()),
+        );
+        let budget = resolver
+            .estimate_gas(
+                Address::ZERO,
+                "@suifrens/core::suifren::mint",
+                &[],
+                vec![amount],
+            )
+            .await
+            .unwrap();
+
+        // 1_000_000 computation + 1000 * 750 overhead + 2_000_000 storage - 500_000 rebate
+        assert_eq!(budget, 3_250_000);
+        inspect.assert_async().await;
+        gas_price.assert_async().await;
+    }
+
+    #[cfg(feature = "network")]
+    struct FixedSigner;
+
+    #[cfg(feature = "network")]
+    impl TransactionSigner for FixedSigner {
+        fn address(&self) -> Address {
+            Address::from_static("0x1")
+        }
+
+        fn sign_transaction(&self, _transaction: &Transaction) -> MvrResult<UserSignature> {
+            Ok(UserSignature::Simple(
+                sui_sdk_types::SimpleSignature::Ed25519 {
+                    signature: sui_sdk_types::Ed25519Signature::new([7; 64]),
+                    public_key: sui_sdk_types::Ed25519PublicKey::new([9; 32]),
+                },
+            ))
+        }
+    }
+
+    #[cfg(feature = "network")]
+    #[tokio::test]
+    async fn test_execute_mvr_call() {
+        use base64ct::{Base64, Encoding};
+        use mockito::Matcher;
+
+        let mut server = mockito::Server::new_async().await;
+        let sender = FixedSigner.address();
+        let rpc = |method: &str, result: serde_json::Value| {
+            (
+                Matcher::PartialJson(serde_json::json!({"method": method})),
+                serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": result}).to_string(),
+            )
+        };
+        let coin = |id: &str, version: &str, balance: &str| {
+            serde_json::json!({
+                "coinObjectId": id, "version": version,
+                "digest": sui_sdk_types::Digest::ZERO.to_string(), "balance": balance,
+            })
+        };
+        for (matcher, body) in [
+            rpc(
+                "sui_devInspectTransactionBlock",
+                serde_json::json!({"effects": {"gasUsed": {
+                    "computationCost": "1000000", "storageCost": "0", "storageRebate": "0",
+                }}}),
+            ),
+            rpc("suix_getReferenceGasPrice", serde_json::json!("1000")),
+            rpc(
+                "suix_getCoins",
+                serde_json::json!({"data": [
+                    coin("0xc1", "4", "500"),
+                    coin("0xc2", "5", "5000000"),
+                    coin("0xc3", "6", "100"),
+                ]}),
+            ),
+        ] {
+            server
+                .mock("POST", "/")
+                .match_body(matcher)
+                .with_status(200)
+                .with_body(body)
+                .create_async()
+                .await;
+        }
+
+        // 1_000_000 computation + 1000 * 1000 overhead, paid from the largest coin only
+        let expected = Transaction {
+            kind: TransactionKind::ProgrammableTransaction(ProgrammableTransaction {
+                inputs: vec![],
+                commands: vec![Command::MoveCall(MoveCall {
+                    package: Address::from_static("0x80d7"),
+                    module: Identifier::from_static("suifren"),
+                    function: Identifier::from_static("mint"),
+                    type_arguments: vec![],
+                    arguments: vec![],
+                })],
+            }),
+            sender,
+            gas_payment: GasPayment {
+                objects: vec![ObjectReference::new(
+                    Address::from_static("0xc2"),
+                    5,
+                    sui_sdk_types::Digest::ZERO,
+                )],
+                owner: sender,
+                price: 1000,
+                budget: 2_000_000,
+            },
+            expiration: TransactionExpiration::None,
+        };
+        let signature = FixedSigner.sign_transaction(&expected).unwrap();
+        let execute = server
+            .mock("POST", "/")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "method": "sui_executeTransactionBlock",
+                "params": [
+                    Base64::encode_string(&bcs::to_bytes(&expected).unwrap()),
+                    [signature.to_base64()],
+                ],
+            })))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": {
+                    "digest": "4mint", "effects": {"status": {"status": "success"}},
+                }})
+                .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let overrides =
+            MvrOverrides::new().with_package("@suifrens/core".to_string(), "0x80d7".to_string());
+        let resolver = MvrResolver::new(
+            MvrConfig::localnet(9)
+                .with_overrides(overrides)
+                .with_sui_rpc_url(server.url()),
+        );
+        let executed = resolver
+            .execute_mvr_call(&FixedSigner, "@suifrens/core::suifren::mint", &[], vec![])
+            .await
+            .unwrap();
+        assert_eq!(executed.digest, "4mint");
+        assert_eq!(executed.effects["status"]["status"], "success");
+        execute.assert_async().await;
+    }
+
+    #[cfg(feature = "network")]
+    #[tokio::test]
+    async fn test_execute_transaction_failure() {
+        let mut server = mockito::Server::new_async().await;
+        let _execute = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_body(
+                serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": {
+                    "digest": "4abort",
+                    "effects": {"status": {"status": "failure", "error": "MoveAbort(..., 1)"}},
+                }})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let resolver = MvrResolver::new(MvrConfig::localnet(9).with_sui_rpc_url(server.url()));
+
+        let mut builder = TransactionBuilder::new();
+        builder.move_call(
+            Function::new(
+                Address::from_static("0x80d7"),
+                Identifier::from_static("suifren"),
+                Identifier::from_static("mint"),
+            ),
+            vec![],
+        );
+        let transaction = build(builder);
+        let signature = FixedSigner.sign_transaction(&transaction).unwrap();
+        match resolver
+            .execute_transaction(&transaction, &[signature])
+            .await
+        {
+            Err(MvrError::InvalidResponse(message)) => {
+                assert!(message.contains("4abort") && message.contains("MoveAbort"))
+            }
+            other => panic!("expected a failed transaction, got {other:?}"),
+        }
+    }
+
+    #[cfg(feature = "network")]
+    #[tokio::test]
+    async fn test_dev_inspect_mvr_call() {
+        let mut server = mockito::Server::new_async().await;
+        let rpc = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"params": [Address::from_static("0x1").to_string()]}),
+            ))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": {
+                    "effects": {},
+                    "results": [{"returnValues": [
+                        [bcs::to_bytes(&42u64).unwrap(), "u64"],
+                        [bcs::to_bytes(&b"fren".to_vec()).unwrap(), "vector<u8>"],
+                    ]}],
+                }})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let aborted = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"params": [Address::ZERO.to_string()]}),
+            ))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": {
+                    "effects": {}, "error": "MoveAbort(..., 1) in command 0",
+                }})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let overrides =
+            MvrOverrides::new().with_package("@suifrens/core".to_string(), "0x80d7".to_string());
+        let resolver = MvrResolver::new(
+            MvrConfig::localnet(9)
+                .with_overrides(overrides)
+                .with_sui_rpc_url(server.url()),
+        );
+        let values = resolver
+            .dev_inspect_mvr_call(
+                Address::from_static("0x1"),
+                "@suifrens/core::suifren::stats",
+                &[],
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].type_tag, TypeTag::U64);
+        assert_eq!(values[0].decode::<u64>().unwrap(), 42);
+        assert_eq!(values[1].decode::<Vec<u8>>().unwrap(), b"fren");
+        assert!(matches!(
+            values[0].decode::<String>(),
+            Err(MvrError::InvalidResponse(_))
+        ));
+        assert!(matches!(
+            resolver
+                .dev_inspect_mvr_call(Address::ZERO, "@suifrens/core::suifren::stats", &[], vec![])
+                .await,
+            Err(MvrError::InvalidResponse(_))
+        ));
+        rpc.assert_async().await;
+        aborted.assert_async().await;
+    }
+}
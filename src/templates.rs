@@ -0,0 +1,323 @@
+//! Ready-made transactions for common MVR calls
+//!
+//! Enabled by the `sui-rust-sdk` feature. A [`TransactionTemplate`] describes a whole
+//! transaction by MVR name: minting an NFT to a recipient, transferring part of a coin
+//! of a given type, or calling an arbitrary target. [`MvrResolver::build_template`]
+//! resolves the names and builds the [`Transaction`] from the sender and gas inputs,
+//! while [`MvrResolver::compile_template`] returns the [`TransactionBuilder`] for
+//! further calls or a sponsored build with [`crate::sui_sdk::build_sponsored`].
+//!
+//! ```rust,ignore
+//! use sui_mvr::templates::{GasInputs, TransactionTemplate};
+//!
+//! let template = TransactionTemplate::TransferCoin {
+//!     coin_type: "@tokens/usdc::usdc::USDC".to_string(),
+//!     coin: Some(usdc_coin),
+//!     amount: 1_000_000,
+//!     recipient,
+//! };
+//! let tx = resolver
+//!     .build_template(&template, sender, GasInputs::new(gas_coins, 10_000_000, 1000))
+//!     .await?;
+//! ```
+
+use crate::error::{MvrError, MvrResult};
+use crate::resolver::MvrResolver;
+use crate::sui_sdk::{
+    Address, Argument, Function, Identifier, ObjectInput, Transaction, TransactionBuilder,
+};
+use crate::tx_spec::{ObjectSpec, ValueSpec};
+
+/// A transaction described by MVR names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionTemplate {
+    /// Call a minting function and transfer the object it returns to `recipient`
+    MintNft {
+        /// `@namespace/package::module::function` target returning the NFT
+        target: String,
+        /// Arguments of the minting function
+        args: Vec<ValueSpec>,
+        /// Owner of the minted NFT
+        recipient: Address,
+    },
+    /// Transfer `amount` of a coin of type `coin_type` to `recipient`, with
+    /// `0x2::pay::split_and_transfer`
+    TransferCoin {
+        /// Coin type, `@`-prefixed names are resolved (`@tokens/usdc::usdc::USDC`)
+        coin_type: String,
+        /// Coin to split the amount from, or the gas coin if `None` (SUI only)
+        coin: Option<ObjectSpec>,
+        /// Amount in the coin's smallest unit
+        amount: u64,
+        /// Receiver of the amount
+        recipient: Address,
+    },
+    /// Call an arbitrary MVR target, discarding its results
+    MoveCall {
+        /// `@namespace/package::module::function` target
+        target: String,
+        /// Type arguments, `@`-prefixed names are resolved
+        type_args: Vec<String>,
+        /// Call arguments in order
+        args: Vec<ValueSpec>,
+    },
+}
+
+/// Gas payment for [`MvrResolver::build_template`], paid by the sender
+#[derive(Clone)]
+pub struct GasInputs {
+    /// Gas coins owned by the sender
+    pub objects: Vec<ObjectInput>,
+    /// Gas budget, see [`MvrResolver::estimate_gas`]
+    pub budget: u64,
+    /// Gas price, usually the network's reference gas price
+    pub price: u64,
+}
+
+impl GasInputs {
+    /// Create a gas payment from the sender's gas coins
+    pub fn new(objects: impl IntoIterator<Item = ObjectInput>, budget: u64, price: u64) -> Self {
+        Self {
+            objects: objects.into_iter().collect(),
+            budget,
+            price,
+        }
+    }
+}
+
+impl MvrResolver {
+    /// Build a [`TransactionTemplate`] into a transaction sent by `sender`
+    ///
+    /// Fails with [`MvrError::InvalidArguments`] for malformed argument values or an
+    /// incomplete transaction, e.g. without gas coins.
+    pub async fn build_template(
+        &self,
+        template: &TransactionTemplate,
+        sender: Address,
+        gas: GasInputs,
+    ) -> MvrResult<Transaction> {
+        let mut builder = self.compile_template(template).await?;
+        builder.set_sender(sender);
+        builder.add_gas_objects(gas.objects);
+        builder.set_gas_budget(gas.budget);
+        builder.set_gas_price(gas.price);
+        builder
+            .try_build()
+            .map_err(|e| MvrError::InvalidArguments(format!("cannot build transaction: {e}")))
+    }
+
+    /// Compile a [`TransactionTemplate`] into a [`TransactionBuilder`]
+    ///
+    /// Names are resolved like [`MvrResolver::add_move_call`]. The builder still needs a
+    /// sender and gas before it can be built.
+    pub async fn compile_template(
+        &self,
+        template: &TransactionTemplate,
+    ) -> MvrResult<TransactionBuilder> {
+        let mut builder = TransactionBuilder::new();
+        match template {
+            TransactionTemplate::MintNft {
+                target,
+                args,
+                recipient,
+            } => {
+                let arguments = self.template_arguments(&mut builder, args).await?;
+                let nft = self
+                    .add_move_call(&mut builder, target, &[], arguments)
+                    .await?;
+                let recipient = builder.pure(recipient);
+                builder.transfer_objects(vec![nft], recipient);
+            }
+            TransactionTemplate::TransferCoin {
+                coin_type,
+                coin,
+                amount,
+                recipient,
+            } => {
+                let coin_type = self.resolve_type_tag(coin_type).await?;
+                let coin = match coin {
+                    Some(coin) => {
+                        self.template_argument(&mut builder, &ValueSpec::Object(coin.clone()))
+                            .await?
+                    }
+                    None => builder.gas(),
+                };
+                let amount = builder.pure(amount);
+                let recipient = builder.pure(recipient);
+                let function = Function::new(
+                    Address::TWO,
+                    Identifier::from_static("pay"),
+                    Identifier::from_static("split_and_transfer"),
+                );
+                builder.move_call(
+                    function.with_type_args(vec![coin_type]),
+                    vec![coin, amount, recipient],
+                );
+            }
+            TransactionTemplate::MoveCall {
+                target,
+                type_args,
+                args,
+            } => {
+                let arguments = self.template_arguments(&mut builder, args).await?;
+                let type_args: Vec<&str> = type_args.iter().map(String::as_str).collect();
+                self.add_move_call(&mut builder, target, &type_args, arguments)
+                    .await?;
+            }
+        }
+        Ok(builder)
+    }
+
+    /// Add each value as a transaction input
+    async fn template_arguments(
+        &self,
+        builder: &mut TransactionBuilder,
+        values: &[ValueSpec],
+    ) -> MvrResult<Vec<Argument>> {
+        let mut arguments = Vec::with_capacity(values.len());
+        for value in values {
+            arguments.push(self.template_argument(builder, value).await?);
+        }
+        Ok(arguments)
+    }
+
+    /// Add a value as a transaction input, failing if it is malformed
+    async fn template_argument(
+        &self,
+        builder: &mut TransactionBuilder,
+        value: &ValueSpec,
+    ) -> MvrResult<Argument> {
+        self.value_argument(builder, value)
+            .await?
+            .ok_or_else(|| MvrError::InvalidArguments(format!("invalid value {value:?}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sui_sdk::test_support::{build, resolver};
+    use sui_sdk_types::Digest;
+
+    fn suifren(function: &'static str) -> Function {
+        Function::new(
+            Address::from_static("0x80d7"),
+            Identifier::from_static("suifren"),
+            Identifier::from_static(function),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_build_mint_nft_template() {
+        let recipient = Address::from_static("0xa11ce");
+        let template = TransactionTemplate::MintNft {
+            target: "@suifrens/core::suifren::mint".to_string(),
+            args: vec![ValueSpec::String("fren".to_string())],
+            recipient,
+        };
+        let gas = GasInputs::new(
+            [ObjectInput::owned(Address::ZERO, 1, Digest::ZERO)],
+            1_000_000,
+            1000,
+        );
+        let tx = resolver()
+            .build_template(&template, Address::ZERO, gas)
+            .await
+            .unwrap();
+
+        let mut direct = TransactionBuilder::new();
+        let name = direct.pure(&"fren".to_string());
+        let nft = direct.move_call(suifren("mint"), vec![name]);
+        let recipient = direct.pure(&recipient);
+        direct.transfer_objects(vec![nft], recipient);
+        assert_eq!(tx, build(direct));
+    }
+
+    #[tokio::test]
+    async fn test_compile_transfer_coin_template() {
+        let resolver = resolver();
+        let recipient = Address::from_static("0xb0b");
+        let template = TransactionTemplate::TransferCoin {
+            coin_type: "@suifrens/core::suifren::SuiFren".to_string(),
+            coin: Some(ObjectSpec {
+                id: "0xc014".to_string(),
+                version: 3,
+                digest: Digest::ZERO.to_string(),
+            }),
+            amount: 250,
+            recipient,
+        };
+        let compiled = resolver.compile_template(&template).await.unwrap();
+
+        let mut direct = TransactionBuilder::new();
+        let coin = direct.object(ObjectInput::owned(
+            Address::from_static("0xc014"),
+            3,
+            Digest::ZERO,
+        ));
+        let amount = direct.pure(&250u64);
+        let to = direct.pure(&recipient);
+        direct.move_call(
+            Function::new(
+                Address::TWO,
+                Identifier::from_static("pay"),
+                Identifier::from_static("split_and_transfer"),
+            )
+            .with_type_args(vec!["0x80d7::suifren::SuiFren".parse().unwrap()]),
+            vec![coin, amount, to],
+        );
+        assert_eq!(build(compiled), build(direct));
+
+        // Without a coin, SUI is split off the gas coin
+        let template = TransactionTemplate::TransferCoin {
+            coin_type: "0x2::sui::SUI".to_string(),
+            coin: None,
+            amount: 250,
+            recipient,
+        };
+        let compiled = resolver.compile_template(&template).await.unwrap();
+        let mut direct = TransactionBuilder::new();
+        let gas = direct.gas();
+        let amount = direct.pure(&250u64);
+        let to = direct.pure(&recipient);
+        direct.move_call(
+            Function::new(
+                Address::TWO,
+                Identifier::from_static("pay"),
+                Identifier::from_static("split_and_transfer"),
+            )
+            .with_type_args(vec!["0x2::sui::SUI".parse().unwrap()]),
+            vec![gas, amount, to],
+        );
+        assert_eq!(build(compiled), build(direct));
+    }
+
+    #[tokio::test]
+    async fn test_compile_move_call_template() {
+        let resolver = resolver();
+        let template = TransactionTemplate::MoveCall {
+            target: "@suifrens/core::suifren::burn".to_string(),
+            type_args: vec!["@suifrens/core::suifren::SuiFren".to_string()],
+            args: vec![ValueSpec::Address("@suifrens/core".to_string())],
+        };
+        let compiled = resolver.compile_template(&template).await.unwrap();
+
+        let mut direct = TransactionBuilder::new();
+        let owner = direct.pure(&Address::from_static("0x80d7"));
+        direct.move_call(
+            suifren("burn").with_type_args(vec!["0x80d7::suifren::SuiFren".parse().unwrap()]),
+            vec![owner],
+        );
+        assert_eq!(build(compiled), build(direct));
+
+        let invalid = TransactionTemplate::MoveCall {
+            target: "@suifrens/core::suifren::burn".to_string(),
+            type_args: vec![],
+            args: vec![ValueSpec::Address("not-an-address".to_string())],
+        };
+        assert!(matches!(
+            resolver.compile_template(&invalid).await,
+            Err(MvrError::InvalidArguments(_))
+        ));
+    }
+}
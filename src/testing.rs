@@ -0,0 +1,859 @@
+//! Test support for code that resolves MVR names
+//!
+//! [`MockResolver`] mirrors the resolution methods of [`crate::MvrResolver`] with
+//! programmable answers, injected latency and failure modes, so downstream crates can
+//! unit-test their retry and fallback logic without a registry or static overrides.
+//!
+//! [`FixtureMode`] makes a real resolver record registry responses to fixture files
+//! and replay them later, so integration tests stop depending on live endpoints.
+
+use crate::error::{validate_package_name, validate_type_name, MvrError, MvrResult};
+#[cfg(all(feature = "network", not(target_arch = "wasm32")))]
+use crate::resolver::RawResponse;
+#[cfg(all(feature = "network", not(target_arch = "wasm32")))]
+use serde::{Deserialize, Serialize};
+#[cfg(all(feature = "network", not(target_arch = "wasm32")))]
+use std::collections::BTreeMap;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Environment variable that switches [`FixtureMode::from_env`] to recording
+pub const RECORD_FIXTURES_ENV: &str = "MVR_RECORD_FIXTURES";
+
+/// Response headers kept in fixture files
+#[cfg(all(feature = "network", not(target_arch = "wasm32")))]
+const RECORDED_HEADERS: [&str; 4] = ["cache-control", "content-type", "etag", "retry-after"];
+
+/// Record registry responses to, or replay them from, a fixture directory
+///
+/// Set with [`crate::MvrConfig::with_fixtures`]. Each request is stored in its own
+/// JSON file named after its method, path and body but not the host, so fixtures
+/// recorded against mainnet replay against any endpoint. Ignored on wasm32.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FixtureMode {
+    /// Send requests to the registry and save every response in the directory
+    Record(PathBuf),
+    /// Answer requests from the directory without touching the network
+    ///
+    /// A request without a recorded response fails with `MvrError::ConfigError`.
+    Replay(PathBuf),
+}
+
+impl FixtureMode {
+    /// Record when [`RECORD_FIXTURES_ENV`] is set, replay otherwise
+    ///
+    /// Lets a test suite re-record its fixtures with `MVR_RECORD_FIXTURES=1 cargo test`.
+    pub fn from_env(dir: impl Into<PathBuf>) -> Self {
+        if std::env::var_os(RECORD_FIXTURES_ENV).is_some() {
+            FixtureMode::Record(dir.into())
+        } else {
+            FixtureMode::Replay(dir.into())
+        }
+    }
+
+    /// The fixture directory
+    pub fn dir(&self) -> &std::path::Path {
+        match self {
+            FixtureMode::Record(dir) | FixtureMode::Replay(dir) => dir,
+        }
+    }
+}
+
+#[cfg(all(feature = "network", not(target_arch = "wasm32")))]
+impl FixtureMode {
+    /// Start recording `request`, see [`Recording::save`]
+    pub(crate) fn recording(&self, request: &reqwest::Request) -> Recording {
+        let (path, request_line, request_body) = self.locate(request);
+        Recording {
+            path,
+            request_line,
+            request_body,
+        }
+    }
+
+    /// Answer `request` from its fixture file
+    pub(crate) fn replay(&self, request: &reqwest::Request) -> MvrResult<RawResponse> {
+        let (path, request_line, _) = self.locate(request);
+        let json = std::fs::read_to_string(&path).map_err(|e| {
+            MvrError::ConfigError(format!(
+                "no fixture for '{request_line}' at '{}' ({e}); record it with {RECORD_FIXTURES_ENV}=1",
+                path.display()
+            ))
+        })?;
+        let fixture: Fixture = serde_json::from_str(&json)?;
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        for (name, value) in &fixture.headers {
+            if let (Ok(name), Ok(value)) = (
+                reqwest::header::HeaderName::from_bytes(name.as_bytes()),
+                reqwest::header::HeaderValue::from_str(value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+        Ok(RawResponse {
+            status: fixture.status,
+            headers,
+            body: fixture.body,
+            request_id: String::new(),
+            context: crate::resolver::request_context(request),
+        })
+    }
+
+    /// Fixture file, request line and body of `request`
+    fn locate(&self, request: &reqwest::Request) -> (PathBuf, String, Option<String>) {
+        let url = request.url();
+        let target = match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_string(),
+        };
+        let request_line = format!("{} {target}", request.method());
+        let request_body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned());
+
+        // FNV-1a, stable across platforms and Rust versions unlike `DefaultHasher`
+        let key = format!("{request_line}\n{}", request_body.as_deref().unwrap_or(""));
+        let hash = key.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+        let file = format!(
+            "{}-{hash:016x}.json",
+            request.method().as_str().to_lowercase()
+        );
+        (self.dir().join(file), request_line, request_body)
+    }
+}
+
+/// A request whose response is about to be recorded
+#[cfg(all(feature = "network", not(target_arch = "wasm32")))]
+pub(crate) struct Recording {
+    path: PathBuf,
+    request_line: String,
+    request_body: Option<String>,
+}
+
+#[cfg(all(feature = "network", not(target_arch = "wasm32")))]
+impl Recording {
+    /// Write `response` to the fixture file, replacing an earlier recording
+    pub(crate) fn save(self, response: &RawResponse) -> MvrResult<()> {
+        let headers = RECORDED_HEADERS
+            .iter()
+            .filter_map(|&name| {
+                let value = response.headers.get(name)?.to_str().ok()?;
+                Some((name.to_string(), value.to_string()))
+            })
+            .collect();
+        let fixture = Fixture {
+            request: self.request_line,
+            request_body: self.request_body,
+            status: response.status,
+            headers,
+            body: response.body.clone(),
+        };
+
+        let write_error = |e: std::io::Error| {
+            MvrError::ConfigError(format!(
+                "failed to write fixture '{}': {e}",
+                self.path.display()
+            ))
+        };
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(write_error)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&fixture)?).map_err(write_error)
+    }
+}
+
+/// On-disk form of a recorded exchange
+#[cfg(all(feature = "network", not(target_arch = "wasm32")))]
+#[derive(Serialize, Deserialize)]
+struct Fixture {
+    /// Request line, for people reading the file
+    request: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    request_body: Option<String>,
+    status: u16,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    body: String,
+}
+
+/// A failure a [`MockResolver`] can be programmed to return
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockFailure {
+    /// The registry does not know the name (404)
+    NotFound,
+    /// The registry rate limits the caller (429)
+    RateLimited { retry_after: Duration },
+    /// The request is abandoned after waiting `after`
+    Timeout { after: Duration },
+    /// The registry answers with a non-success status
+    Server { status_code: u16 },
+}
+
+impl MockFailure {
+    fn to_error(&self, name: &str, is_type: bool) -> MvrError {
+        match self {
+            MockFailure::NotFound if is_type => MvrError::type_not_found(name.to_string()),
+            MockFailure::NotFound => MvrError::package_not_found(name.to_string()),
+            MockFailure::RateLimited { retry_after } => MvrError::rate_limited(*retry_after),
+            MockFailure::Timeout { after } => MvrError::timeout(*after),
+            MockFailure::Server { status_code } => {
+                MvrError::server(*status_code, format!("mock failure for {name}"))
+            }
+        }
+    }
+}
+
+/// Programmable stand-in for [`crate::MvrResolver`]
+///
+/// Names without a programmed answer resolve as not found. Failures registered with
+/// [`MockResolver::with_transient_failure`] are returned first, in order, before the
+/// name answers normally, which is how retry loops are usually exercised.
+#[derive(Debug, Default)]
+pub struct MockResolver {
+    packages: HashMap<String, String>,
+    types: HashMap<String, String>,
+    failures: HashMap<String, MockFailure>,
+    transient_failures: Mutex<HashMap<String, VecDeque<MockFailure>>>,
+    latency: Duration,
+    calls: Mutex<HashMap<String, usize>>,
+}
+
+impl MockResolver {
+    /// Create a mock that knows no names
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer `name` with `address`
+    pub fn with_package(mut self, name: String, address: String) -> Self {
+        self.packages.insert(name, address);
+        self
+    }
+
+    /// Answer `name` with `type_signature`
+    pub fn with_type(mut self, name: String, type_signature: String) -> Self {
+        self.types.insert(name, type_signature);
+        self
+    }
+
+    /// Delay every lookup by `latency`
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Fail every lookup of `name` with `failure`
+    pub fn with_failure(mut self, name: String, failure: MockFailure) -> Self {
+        self.failures.insert(name, failure);
+        self
+    }
+
+    /// Fail the next `times` lookups of `name` with `failure`, then answer normally
+    ///
+    /// Calling this repeatedly for the same name queues the failures in order.
+    pub fn with_transient_failure(
+        mut self,
+        name: String,
+        failure: MockFailure,
+        times: usize,
+    ) -> Self {
+        if let Ok(transient) = self.transient_failures.get_mut() {
+            transient
+                .entry(name)
+                .or_default()
+                .extend(std::iter::repeat_n(failure, times));
+        }
+        self
+    }
+
+    /// Number of lookups made for `name` so far, including failed ones
+    pub fn calls(&self, name: &str) -> usize {
+        self.calls
+            .lock()
+            .map(|calls| calls.get(name).copied().unwrap_or(0))
+            .unwrap_or(0)
+    }
+
+    /// Resolve a package name to its programmed address
+    pub async fn resolve_package(&self, package_name: &str) -> MvrResult<String> {
+        validate_package_name(package_name)?;
+        self.lookup(package_name, false).await
+    }
+
+    /// Resolve a type name to its programmed signature
+    pub async fn resolve_type(&self, type_name: &str) -> MvrResult<String> {
+        validate_type_name(type_name)?;
+        self.lookup(type_name, true).await
+    }
+
+    /// Batch resolve packages
+    ///
+    /// Like the real resolver, names that are not found are left out of the result;
+    /// any other failure fails the whole batch.
+    pub async fn resolve_packages(
+        &self,
+        package_names: &[&str],
+    ) -> MvrResult<HashMap<String, String>> {
+        for &name in package_names {
+            validate_package_name(name)?;
+        }
+        self.lookup_batch(package_names, false).await
+    }
+
+    /// Batch resolve types, see [`MockResolver::resolve_packages`]
+    pub async fn resolve_types(&self, type_names: &[&str]) -> MvrResult<HashMap<String, String>> {
+        for &name in type_names {
+            validate_type_name(name)?;
+        }
+        self.lookup_batch(type_names, true).await
+    }
+
+    async fn lookup_batch(
+        &self,
+        names: &[&str],
+        is_type: bool,
+    ) -> MvrResult<HashMap<String, String>> {
+        let mut results = HashMap::new();
+        for &name in names {
+            match self.lookup(name, is_type).await {
+                Ok(value) => {
+                    results.insert(name.to_string(), value);
+                }
+                Err(MvrError::PackageNotFound { .. } | MvrError::TypeNotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(results)
+    }
+
+    async fn lookup(&self, name: &str, is_type: bool) -> MvrResult<String> {
+        if let Ok(mut calls) = self.calls.lock() {
+            *calls.entry(name.to_string()).or_default() += 1;
+        }
+        if !self.latency.is_zero() {
+            crate::sleep(self.latency).await;
+        }
+
+        let failure = self
+            .transient_failures
+            .lock()
+            .ok()
+            .and_then(|mut transient| transient.get_mut(name)?.pop_front())
+            .or_else(|| self.failures.get(name).cloned());
+        if let Some(failure) = failure {
+            if let MockFailure::Timeout { after } = failure {
+                crate::sleep(after).await;
+            }
+            return Err(failure.to_error(name, is_type));
+        }
+
+        let answers = if is_type { &self.types } else { &self.packages };
+        answers
+            .get(name)
+            .cloned()
+            .ok_or_else(|| MockFailure::NotFound.to_error(name, is_type))
+    }
+}
+
+/// In-process HTTP server speaking the MVR resolution endpoints
+///
+/// Serves `GET /v1/resolution/{name}`, `GET /v1/struct-definition/{name}`, the bulk
+/// `POST /v1/resolution/bulk` and `POST /v1/struct-definition/bulk` endpoints and
+/// the legacy `GET /resolve/package/{name}`, `GET /resolve/type/{name}` and
+/// `POST /resolve/batch` on a local port, so tests exercise a real
+/// [`crate::MvrResolver`] over HTTP, including its retry, rate limit and timeout
+/// handling. Answers and faults can be changed while the server runs. The server
+/// stops when dropped.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct MockMvrServer {
+    url: String,
+    state: std::sync::Arc<ServerState>,
+    _shutdown: tokio::sync::oneshot::Sender<()>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+struct ServerState {
+    packages: Mutex<HashMap<String, String>>,
+    types: Mutex<HashMap<String, String>>,
+    faults: Mutex<VecDeque<MockFailure>>,
+    latency: Mutex<Duration>,
+    requests: std::sync::atomic::AtomicUsize,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl MockMvrServer {
+    /// Start a server on a free local port
+    pub async fn start() -> MvrResult<Self> {
+        use axum::routing::{get, post};
+
+        let bind_error = |e: std::io::Error| {
+            MvrError::ConfigError(format!("failed to start mock MVR server: {e}"))
+        };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(bind_error)?;
+        let url = format!("http://{}", listener.local_addr().map_err(bind_error)?);
+
+        let state = std::sync::Arc::new(ServerState::default());
+        let app = axum::Router::new()
+            .route("/v1/resolution/{*name}", get(serve_resolution))
+            .route(
+                "/v1/struct-definition/{*name}",
+                get(serve_struct_definition),
+            )
+            .route("/v1/resolution/bulk", post(serve_bulk_packages))
+            .route("/v1/struct-definition/bulk", post(serve_bulk_types))
+            .route("/resolve/package/{*name}", get(serve_package))
+            .route("/resolve/type/{*name}", get(serve_type))
+            .route("/resolve/batch", post(serve_batch))
+            .with_state(state.clone());
+
+        let (shutdown, stopped) = tokio::sync::oneshot::channel::<()>();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app)
+                .with_graceful_shutdown(async {
+                    let _ = stopped.await;
+                })
+                .await;
+        });
+
+        Ok(Self {
+            url,
+            state,
+            _shutdown: shutdown,
+        })
+    }
+
+    /// Base URL of the server, e.g. `http://127.0.0.1:41234`
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Resolver configuration pointing at the server
+    pub fn config(&self) -> crate::MvrConfig {
+        crate::MvrConfig::default().with_endpoint(self.url.clone())
+    }
+
+    /// Serve `address` for package `name`
+    pub fn add_package(&self, name: &str, address: &str) {
+        if let Ok(mut packages) = self.state.packages.lock() {
+            packages.insert(name.to_string(), address.to_string());
+        }
+    }
+
+    /// Serve `type_signature` for type `name`
+    pub fn add_type(&self, name: &str, type_signature: &str) {
+        if let Ok(mut types) = self.state.types.lock() {
+            types.insert(name.to_string(), type_signature.to_string());
+        }
+    }
+
+    /// Answer the next `times` requests with `failure`, then serve normally
+    ///
+    /// `MockFailure::Timeout` delays the response by `after` instead, so a resolver
+    /// with a shorter timeout gives up on it.
+    pub fn fail_next(&self, failure: MockFailure, times: usize) {
+        if let Ok(mut faults) = self.state.faults.lock() {
+            faults.extend(std::iter::repeat_n(failure, times));
+        }
+    }
+
+    /// Delay every response by `latency`
+    pub fn set_latency(&self, latency: Duration) {
+        if let Ok(mut current) = self.state.latency.lock() {
+            *current = latency;
+        }
+    }
+
+    /// Number of requests received so far
+    pub fn requests(&self) -> usize {
+        self.state
+            .requests
+            .load(std::sync::atomic::Ordering::SeqCst)
+    }
+}
+
+/// Count the request, apply latency and answer with the next queued fault, if any
+#[cfg(not(target_arch = "wasm32"))]
+async fn admit(state: &ServerState) -> Result<(), axum::response::Response> {
+    use axum::http::{header, StatusCode};
+    use axum::response::IntoResponse;
+
+    state
+        .requests
+        .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    let latency = state.latency.lock().map(|l| *l).unwrap_or_default();
+    if !latency.is_zero() {
+        tokio::time::sleep(latency).await;
+    }
+
+    let fault = state.faults.lock().ok().and_then(|mut f| f.pop_front());
+    match fault {
+        None => Ok(()),
+        Some(MockFailure::Timeout { after }) => {
+            tokio::time::sleep(after).await;
+            Ok(())
+        }
+        Some(MockFailure::NotFound) => Err(StatusCode::NOT_FOUND.into_response()),
+        Some(MockFailure::RateLimited { retry_after }) => Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after.as_secs().to_string())],
+        )
+            .into_response()),
+        Some(MockFailure::Server { status_code }) => Err((
+            StatusCode::from_u16(status_code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            "mock failure",
+        )
+            .into_response()),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn serve_resolution(
+    axum::extract::State(state): axum::extract::State<std::sync::Arc<ServerState>>,
+    axum::extract::Path(name): axum::extract::Path<String>,
+) -> axum::response::Response {
+    serve_single(&state, &state.packages, &name, "package_id").await
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn serve_struct_definition(
+    axum::extract::State(state): axum::extract::State<std::sync::Arc<ServerState>>,
+    axum::extract::Path(name): axum::extract::Path<String>,
+) -> axum::response::Response {
+    serve_single(&state, &state.types, &name, "type_tag").await
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn serve_package(
+    axum::extract::State(state): axum::extract::State<std::sync::Arc<ServerState>>,
+    axum::extract::Path(name): axum::extract::Path<String>,
+) -> axum::response::Response {
+    serve_single(&state, &state.packages, &name, "address").await
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn serve_type(
+    axum::extract::State(state): axum::extract::State<std::sync::Arc<ServerState>>,
+    axum::extract::Path(name): axum::extract::Path<String>,
+) -> axum::response::Response {
+    serve_single(&state, &state.types, &name, "type_signature").await
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn serve_single(
+    state: &ServerState,
+    answers: &Mutex<HashMap<String, String>>,
+    name: &str,
+    field: &str,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    if let Err(response) = admit(state).await {
+        return response;
+    }
+    let answer = answers.lock().ok().and_then(|a| a.get(name).cloned());
+    match answer {
+        Some(value) => axum::Json(serde_json::json!({ field: value })).into_response(),
+        None => axum::http::StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(serde::Deserialize)]
+struct MockBulkRequest {
+    #[serde(default)]
+    names: Vec<String>,
+    #[serde(default)]
+    types: Vec<String>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn serve_bulk_packages(
+    axum::extract::State(state): axum::extract::State<std::sync::Arc<ServerState>>,
+    axum::Json(request): axum::Json<MockBulkRequest>,
+) -> axum::response::Response {
+    serve_bulk(&state, &state.packages, request.names, "package_id").await
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn serve_bulk_types(
+    axum::extract::State(state): axum::extract::State<std::sync::Arc<ServerState>>,
+    axum::Json(request): axum::Json<MockBulkRequest>,
+) -> axum::response::Response {
+    serve_bulk(&state, &state.types, request.types, "type_tag").await
+}
+
+/// Answer a bulk request with `{"resolution": {name: {field: value}}}` for known names
+#[cfg(not(target_arch = "wasm32"))]
+async fn serve_bulk(
+    state: &ServerState,
+    answers: &Mutex<HashMap<String, String>>,
+    names: Vec<String>,
+    field: &str,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    if let Err(response) = admit(state).await {
+        return response;
+    }
+    let answers = answers.lock().map(|a| a.clone()).unwrap_or_default();
+    let resolution = names
+        .into_iter()
+        .filter_map(|name| {
+            let value = answers.get(&name)?.clone();
+            Some((name, serde_json::json!({ field: value })))
+        })
+        .collect::<serde_json::Map<_, _>>();
+    axum::Json(serde_json::json!({ "resolution": resolution })).into_response()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(serde::Deserialize)]
+struct MockBatchRequest {
+    packages: Option<Vec<String>>,
+    types: Option<Vec<String>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn serve_batch(
+    axum::extract::State(state): axum::extract::State<std::sync::Arc<ServerState>>,
+    axum::Json(request): axum::Json<MockBatchRequest>,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    if let Err(response) = admit(&state).await {
+        return response;
+    }
+    let known = |answers: &Mutex<HashMap<String, String>>, names: Option<Vec<String>>| {
+        let answers = answers.lock().map(|a| a.clone()).unwrap_or_default();
+        names.map(|names| {
+            names
+                .into_iter()
+                .filter_map(|name| {
+                    let value = answers.get(&name)?.clone();
+                    Some((name, value))
+                })
+                .collect::<HashMap<_, _>>()
+        })
+    };
+    axum::Json(serde_json::json!({
+        "packages": known(&state.packages, request.packages),
+        "types": known(&state.types, request.types),
+    }))
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_programmed_answers() {
+        let mock = MockResolver::new()
+            .with_package("@test/pkg".to_string(), "0x1".to_string())
+            .with_type("@test/pkg::m::T".to_string(), "0x1::m::T".to_string());
+
+        assert_eq!(mock.resolve_package("@test/pkg").await.unwrap(), "0x1");
+        assert_eq!(
+            mock.resolve_type("@test/pkg::m::T").await.unwrap(),
+            "0x1::m::T"
+        );
+        assert!(matches!(
+            mock.resolve_package("@test/missing").await,
+            Err(MvrError::PackageNotFound { .. })
+        ));
+        assert!(matches!(
+            mock.resolve_package("invalid").await,
+            Err(MvrError::InvalidPackageName(_))
+        ));
+        assert_eq!(mock.calls("@test/pkg"), 1);
+        assert_eq!(mock.calls("invalid"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_transient_failures_then_success() {
+        let mock = MockResolver::new()
+            .with_package("@test/pkg".to_string(), "0x1".to_string())
+            .with_transient_failure(
+                "@test/pkg".to_string(),
+                MockFailure::RateLimited {
+                    retry_after: Duration::from_secs(2),
+                },
+                1,
+            )
+            .with_transient_failure(
+                "@test/pkg".to_string(),
+                MockFailure::Server { status_code: 503 },
+                1,
+            );
+
+        let first = mock.resolve_package("@test/pkg").await.unwrap_err();
+        assert_eq!(first.retry_delay(), Some(Duration::from_secs(2)));
+        let second = mock.resolve_package("@test/pkg").await.unwrap_err();
+        assert!(second.is_retryable());
+        assert_eq!(mock.resolve_package("@test/pkg").await.unwrap(), "0x1");
+        assert_eq!(mock.calls("@test/pkg"), 3);
+    }
+
+    #[cfg(all(feature = "network", not(target_arch = "wasm32")))]
+    #[tokio::test]
+    async fn test_record_then_replay_fixtures() {
+        use crate::{MvrConfig, MvrResolver};
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut server = mockito::Server::new_async().await;
+        let single = server
+            .mock("GET", "/v1/resolution/@test/pkg")
+            .with_status(200)
+            .with_header("etag", "\"v1\"")
+            .with_body(r#"{"address": "0x1"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let batch = server
+            .mock("POST", "/v1/resolution/bulk")
+            .with_status(200)
+            .with_body(r#"{"resolution": {"@test/a": {"package_id": "0xa"}, "@test/b": {"package_id": "0xb"}}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let missing = server
+            .mock("GET", "/v1/resolution/@test/missing")
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let recorder = MvrResolver::new(
+            MvrConfig::default()
+                .with_endpoint(server.url())
+                .with_fixtures(FixtureMode::Record(dir.path().to_path_buf())),
+        );
+        recorder.resolve_package("@test/pkg").await.unwrap();
+        recorder
+            .resolve_packages(&["@test/a", "@test/b"])
+            .await
+            .unwrap();
+        assert!(recorder.resolve_package("@test/missing").await.is_err());
+        single.assert_async().await;
+        batch.assert_async().await;
+        missing.assert_async().await;
+
+        // Nothing listens on port 9, so answers can only come from the fixtures
+        let replayer = MvrResolver::new(
+            MvrConfig::localnet(9).with_fixtures(FixtureMode::Replay(dir.path().to_path_buf())),
+        );
+        let detailed = replayer
+            .resolve_package_detailed("@test/pkg")
+            .await
+            .unwrap();
+        assert_eq!(&*detailed.value, "0x1");
+        assert_eq!(detailed.resolved_against_version.as_deref(), Some("\"v1\""));
+        assert_eq!(
+            replayer
+                .resolve_packages(&["@test/a", "@test/b"])
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(matches!(
+            replayer.resolve_package("@test/missing").await,
+            Err(MvrError::PackageNotFound { .. })
+        ));
+        assert!(matches!(
+            replayer.resolve_package("@test/unrecorded").await,
+            Err(MvrError::ConfigError(_))
+        ));
+    }
+
+    #[cfg(all(feature = "network", not(target_arch = "wasm32")))]
+    #[tokio::test]
+    async fn test_mock_server_speaks_mvr_endpoints() {
+        use crate::MvrResolver;
+
+        let server = MockMvrServer::start().await.unwrap();
+        server.add_package("@test/pkg", "0x1");
+        server.add_type("@test/pkg::m::T", "0x1::m::T");
+
+        let resolver = MvrResolver::new(server.config());
+        assert_eq!(resolver.resolve_package("@test/pkg").await.unwrap(), "0x1");
+        assert_eq!(
+            resolver.resolve_type("@test/pkg::m::T").await.unwrap(),
+            "0x1::m::T"
+        );
+        let batch = resolver
+            .resolve_packages(&["@test/pkg", "@test/missing"])
+            .await
+            .unwrap();
+        assert_eq!(batch.len(), 1);
+        assert!(matches!(
+            resolver.resolve_package("@test/missing").await,
+            Err(MvrError::PackageNotFound { .. })
+        ));
+        assert_eq!(server.requests(), 4);
+
+        server.fail_next(
+            MockFailure::RateLimited {
+                retry_after: Duration::from_secs(7),
+            },
+            1,
+        );
+        server.fail_next(MockFailure::Server { status_code: 503 }, 1);
+        let fresh = MvrResolver::new(server.config());
+        assert!(matches!(
+            fresh.resolve_package("@test/pkg").await,
+            Err(MvrError::RateLimitExceeded {
+                retry_after_secs: 7,
+                ..
+            })
+        ));
+        assert!(matches!(
+            fresh.resolve_package("@test/pkg").await,
+            Err(MvrError::ServerError {
+                status_code: 503,
+                ..
+            })
+        ));
+        assert_eq!(fresh.resolve_package("@test/pkg").await.unwrap(), "0x1");
+    }
+
+    #[tokio::test]
+    async fn test_timeout_and_latency() {
+        let mock = MockResolver::new()
+            .with_latency(Duration::from_millis(20))
+            .with_package("@test/pkg".to_string(), "0x1".to_string())
+            .with_failure(
+                "@test/slow".to_string(),
+                MockFailure::Timeout {
+                    after: Duration::from_millis(30),
+                },
+            );
+
+        let start = std::time::Instant::now();
+        assert!(matches!(
+            mock.resolve_package("@test/slow").await,
+            Err(MvrError::Timeout { .. })
+        ));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        // Not found names are left out of batches, other failures fail them
+        let found = mock
+            .resolve_packages(&["@test/pkg", "@test/missing"])
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert!(mock
+            .resolve_packages(&["@test/pkg", "@test/slow"])
+            .await
+            .is_err());
+    }
+}
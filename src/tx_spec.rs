@@ -0,0 +1,342 @@
+//! Declarative transaction specs compiled to programmable transactions
+//!
+//! Enabled by the `sui-rust-sdk` feature. A [`TransactionSpec`] lists Move calls by MVR
+//! target with typed arguments, so transactions can be authored as JSON, as TOML with
+//! the `toml` feature, or in any other serde format, and compiled with
+//! [`MvrResolver::compile_transaction_spec`].
+//! Arguments are either typed values such as `{"u64": "1000"}` or references to the
+//! gas coin and earlier results: `"$gas"`, `"$result.N"` for the result of call `N`
+//! and `"$result.N.M"` for its `M`th return value.
+//!
+//! ```json
+//! {"calls": [
+//!     {"target": "@suifrens/core::suifren::mint", "args": [{"u64": "1"}]},
+//!     {"target": "@suifrens/core::suifren::rename", "args": ["$result.0", {"string": "fren"}]}
+//! ]}
+//! ```
+
+use crate::error::{MvrError, MvrResult};
+use crate::resolver::MvrResolver;
+use crate::sui_sdk::{Address, Argument, ObjectInput, TransactionBuilder};
+use serde::{Deserialize, Serialize};
+
+/// A transaction described as a list of Move calls
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionSpec {
+    /// Calls in execution order
+    pub calls: Vec<CallSpec>,
+}
+
+impl TransactionSpec {
+    /// Parse a spec from JSON
+    pub fn from_json(json: &str) -> MvrResult<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Parse a spec from TOML, with calls as `[[calls]]` tables
+    ///
+    /// ```toml
+    /// [[calls]]
+    /// target = "@suifrens/core::suifren::mint"
+    /// args = [{ u64 = "1" }, "$gas"]
+    /// ```
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> MvrResult<Self> {
+        toml::from_str(toml)
+            .map_err(|e| MvrError::InvalidArguments(format!("invalid transaction spec: {e}")))
+    }
+}
+
+/// A single Move call in a [`TransactionSpec`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallSpec {
+    /// `@namespace/package::module::function` target
+    pub target: String,
+    /// Type arguments, `@`-prefixed names are resolved
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub type_args: Vec<String>,
+    /// Call arguments in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<ArgSpec>,
+}
+
+/// An argument of a [`CallSpec`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ArgSpec {
+    /// `$gas`, `$result.N` or `$result.N.M`
+    Reference(String),
+    /// A typed value
+    Value(ValueSpec),
+}
+
+/// A typed argument value, written as `{"<type>": <value>}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueSpec {
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    /// A number, or a string for values JSON tooling cannot represent exactly
+    U64(IntSpec),
+    /// A number, or a string for values JSON tooling cannot represent exactly
+    U128(IntSpec),
+    /// An address, or an MVR package name resolved to its address
+    Address(String),
+    String(String),
+    /// An owned or immutable object
+    Object(ObjectSpec),
+    /// A shared object
+    Shared(SharedObjectSpec),
+}
+
+/// An integer given as a JSON number or string
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum IntSpec {
+    Number(u64),
+    String(String),
+}
+
+impl IntSpec {
+    fn parse<T: std::str::FromStr>(&self) -> Option<T> {
+        match self {
+            IntSpec::Number(n) => n.to_string().parse().ok(),
+            IntSpec::String(s) => s.parse().ok(),
+        }
+    }
+}
+
+/// Reference to an owned or immutable object
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectSpec {
+    /// Object ID
+    pub id: String,
+    /// Object version
+    pub version: u64,
+    /// Object digest (base58)
+    pub digest: String,
+}
+
+/// Reference to a shared object
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharedObjectSpec {
+    /// Object ID
+    pub id: String,
+    /// Version at which the object became shared
+    pub initial_shared_version: u64,
+    /// Whether the call takes the object by mutable reference
+    #[serde(default)]
+    pub mutable: bool,
+}
+
+impl MvrResolver {
+    /// Compile a [`TransactionSpec`] into a [`TransactionBuilder`]
+    ///
+    /// Targets, type arguments and `@`-prefixed addresses are resolved like
+    /// [`MvrResolver::add_move_call`]. The builder still needs a sender and gas before
+    /// it can be built, e.g. with [`crate::sui_sdk::build_sponsored`]. Invalid values and
+    /// references to later calls fail with [`MvrError::InvalidArguments`].
+    pub async fn compile_transaction_spec(
+        &self,
+        spec: &TransactionSpec,
+    ) -> MvrResult<TransactionBuilder> {
+        let mut builder = TransactionBuilder::new();
+        let mut results = Vec::with_capacity(spec.calls.len());
+        for (index, call) in spec.calls.iter().enumerate() {
+            let invalid = |reason: String| {
+                MvrError::InvalidArguments(format!("call {index} ({}): {reason}", call.target))
+            };
+            let mut arguments = Vec::with_capacity(call.args.len());
+            for arg in &call.args {
+                let argument = match arg {
+                    ArgSpec::Reference(reference) => {
+                        reference_argument(&mut builder, &results, reference)
+                            .ok_or_else(|| invalid(format!("invalid reference '{reference}'")))?
+                    }
+                    ArgSpec::Value(value) => self
+                        .value_argument(&mut builder, value)
+                        .await?
+                        .ok_or_else(|| invalid(format!("invalid value {value:?}")))?,
+                };
+                arguments.push(argument);
+            }
+
+            let type_args: Vec<&str> = call.type_args.iter().map(String::as_str).collect();
+            let result = self
+                .add_move_call(&mut builder, &call.target, &type_args, arguments)
+                .await?;
+            results.push(result);
+        }
+        Ok(builder)
+    }
+
+    /// Add a typed value as a transaction input, `None` if it is malformed
+    pub(crate) async fn value_argument(
+        &self,
+        builder: &mut TransactionBuilder,
+        value: &ValueSpec,
+    ) -> MvrResult<Option<Argument>> {
+        let argument = match value {
+            ValueSpec::Bool(b) => builder.pure(b),
+            ValueSpec::U8(n) => builder.pure(n),
+            ValueSpec::U16(n) => builder.pure(n),
+            ValueSpec::U32(n) => builder.pure(n),
+            ValueSpec::U64(n) => match n.parse::<u64>() {
+                Some(n) => builder.pure(&n),
+                None => return Ok(None),
+            },
+            ValueSpec::U128(n) => match n.parse::<u128>() {
+                Some(n) => builder.pure(&n),
+                None => return Ok(None),
+            },
+            ValueSpec::Address(address) if address.starts_with('@') => {
+                builder.pure(&self.resolve_address(address).await?)
+            }
+            ValueSpec::Address(address) => match address.parse::<Address>() {
+                Ok(address) => builder.pure(&address),
+                Err(_) => return Ok(None),
+            },
+            ValueSpec::String(s) => builder.pure(s),
+            ValueSpec::Object(object) => match (object.id.parse(), object.digest.parse()) {
+                (Ok(id), Ok(digest)) => {
+                    builder.object(ObjectInput::owned(id, object.version, digest))
+                }
+                _ => return Ok(None),
+            },
+            ValueSpec::Shared(object) => match object.id.parse() {
+                Ok(id) => builder.object(ObjectInput::shared(
+                    id,
+                    object.initial_shared_version,
+                    object.mutable,
+                )),
+                Err(_) => return Ok(None),
+            },
+        };
+        Ok(Some(argument))
+    }
+}
+
+/// Look up `$gas`, `$result.N` or `$result.N.M` among the results so far
+fn reference_argument(
+    builder: &mut TransactionBuilder,
+    results: &[Argument],
+    reference: &str,
+) -> Option<Argument> {
+    if reference == "$gas" {
+        return Some(builder.gas());
+    }
+    let mut parts = reference.strip_prefix("$result.")?.split('.');
+    let result = *results.get(parts.next()?.parse::<usize>().ok()?)?;
+    match (parts.next(), parts.next()) {
+        (None, _) => Some(result),
+        (Some(sub_index), None) => {
+            let sub_index: usize = sub_index.parse().ok()?;
+            result.to_nested(sub_index + 1).pop()
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sui_sdk::test_support::{build, resolver};
+    use crate::sui_sdk::{Function, Identifier};
+
+    #[tokio::test]
+    async fn test_compile_transaction_spec() {
+        let spec = TransactionSpec::from_json(
+            r#"{"calls": [
+                {"target": "@suifrens/core::suifren::mint", "args": [
+                    {"u64": "18446744073709551615"}, {"address": "@suifrens/core"}
+                ]},
+                {"target": "@suifrens/core::suifren::split", "type_args": ["0x2::sui::SUI"], "args": [
+                    "$result.0", "$gas", {"shared": {"id": "0x6", "initial_shared_version": 1}}
+                ]},
+                {"target": "@suifrens/core::suifren::merge", "args": ["$result.1.1", {"bool": true}]}
+            ]}"#,
+        )
+        .unwrap();
+        let compiled = resolver().compile_transaction_spec(&spec).await.unwrap();
+
+        let function = |name: &'static str| {
+            Function::new(
+                Address::from_static("0x80d7"),
+                Identifier::from_static("suifren"),
+                Identifier::from_static(name),
+            )
+        };
+        let mut direct = TransactionBuilder::new();
+        let amount = direct.pure(&u64::MAX);
+        let owner = direct.pure(&Address::from_static("0x80d7"));
+        let minted = direct.move_call(function("mint"), vec![amount, owner]);
+        let gas = direct.gas();
+        let clock = direct.object(ObjectInput::shared(Address::from_static("0x6"), 1, false));
+        let split = direct.move_call(
+            function("split").with_type_args(vec!["0x2::sui::SUI".parse().unwrap()]),
+            vec![minted, gas, clock],
+        );
+        let flag = direct.pure(&true);
+        direct.move_call(function("merge"), vec![split.to_nested(2)[1], flag]);
+
+        assert_eq!(build(compiled), build(direct));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_transaction_spec_from_toml() {
+        let toml = r#"
+            [[calls]]
+            target = "@suifrens/core::suifren::mint"
+            args = [{ u64 = "18446744073709551615" }, { address = "@suifrens/core" }]
+
+            [[calls]]
+            target = "@suifrens/core::suifren::split"
+            type_args = ["0x2::sui::SUI"]
+            args = ["$result.0", "$gas", { shared = { id = "0x6", initial_shared_version = 1 } }]
+        "#;
+        let json = r#"{"calls": [
+            {"target": "@suifrens/core::suifren::mint", "args": [
+                {"u64": "18446744073709551615"}, {"address": "@suifrens/core"}
+            ]},
+            {"target": "@suifrens/core::suifren::split", "type_args": ["0x2::sui::SUI"], "args": [
+                "$result.0", "$gas", {"shared": {"id": "0x6", "initial_shared_version": 1}}
+            ]}
+        ]}"#;
+        assert_eq!(
+            TransactionSpec::from_toml(toml).unwrap(),
+            TransactionSpec::from_json(json).unwrap()
+        );
+        assert!(matches!(
+            TransactionSpec::from_toml("[[calls]]\nargs = []"),
+            Err(MvrError::InvalidArguments(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_compile_invalid_spec() {
+        let resolver = resolver();
+        for args in [
+            r#"["$result.0"]"#,
+            r#"["$result"]"#,
+            r#"["gas"]"#,
+            r#"[{"u64": "-1"}]"#,
+            r#"[{"address": "not-an-address"}]"#,
+        ] {
+            let spec = TransactionSpec::from_json(&format!(
+                r#"{{"calls": [{{"target": "@suifrens/core::suifren::mint", "args": {args}}}]}}"#
+            ))
+            .unwrap();
+            assert!(
+                matches!(
+                    resolver.compile_transaction_spec(&spec).await,
+                    Err(MvrError::InvalidArguments(_))
+                ),
+                "{args}"
+            );
+        }
+        assert!(TransactionSpec::from_json(r#"{"calls": [{"args": []}]}"#).is_err());
+    }
+}
@@ -1,30 +1,132 @@
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use tokio::time::Duration;
+use crate::error::{normalize_package_name, normalize_type_name, MvrError, MvrResult};
+use serde::{Deserialize, Serialize, Serializer};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Environment variable holding the endpoint (URL or bare port) of a local registry
+pub const LOCAL_ENDPOINT_ENV: &str = "MVR_LOCAL_ENDPOINT";
+
+/// Environment variable holding the path of a file that a test harness writes the
+/// local registry endpoint (URL or bare port) to once it has bound a port
+pub const LOCAL_ENDPOINT_FILE_ENV: &str = "MVR_LOCAL_ENDPOINT_FILE";
 
 /// Configuration for the MVR resolver
 #[derive(Debug, Clone)]
 pub struct MvrConfig {
     /// The MVR API endpoint URL
     pub endpoint_url: String,
+    /// Name of the network the registry serves, e.g. `mainnet`, reported in logs
+    pub network_name: Option<String>,
     /// Cache time-to-live duration
     pub cache_ttl: Duration,
+    /// TTL for package addresses, falls back to `cache_ttl` when unset
+    pub package_cache_ttl: Option<Duration>,
+    /// TTL for type signatures, falls back to `cache_ttl` when unset
+    pub type_cache_ttl: Option<Duration>,
+    /// Random spread applied to each entry's TTL, in percent (0 disables jitter)
+    pub cache_ttl_jitter_percent: u8,
+    /// Maximum number of cached resolutions
+    pub cache_max_entries: usize,
+    /// Budget for the estimated memory of cached resolutions, unlimited when unset
+    pub cache_max_bytes: Option<usize>,
+    /// Snapshot file the cache is restored from on creation and saved to on shutdown
+    pub cache_persist_path: Option<PathBuf>,
+    /// Record registry responses to, or replay them from, fixture files
+    #[cfg(feature = "testing")]
+    pub fixtures: Option<crate::testing::FixtureMode>,
+    /// Refresh cache hits in the background once they are within this share of their
+    /// TTL, in percent (0 disables refresh-ahead)
+    pub refresh_ahead_percent: u8,
     /// Static overrides for packages and types
     pub overrides: Option<MvrOverrides>,
+    /// Sui fullnode JSON-RPC URL used to inspect resolved packages
+    pub sui_rpc_url: Option<String>,
+    /// Application identifier appended to the `User-Agent`, e.g. `my-indexer/2.1`
+    pub app_name: Option<String>,
+    /// Look up similar names when a package is not found
+    pub name_suggestions: bool,
+    /// Trim, lowercase and strip a trailing `/` from names before validating them
+    pub normalize_names: bool,
+    /// Short names standing in for full package names, e.g. `core` for `@suifrens/core`
+    pub aliases: HashMap<String, String>,
     /// HTTP request timeout
     pub timeout: Duration,
     /// Maximum number of concurrent requests
     pub max_concurrent_requests: usize,
+    /// Share of `max_concurrent_requests` that only package lookups may use
+    pub reserved_package_permits: usize,
+    /// Separate limit for batch requests, which otherwise share `max_concurrent_requests`
+    pub batch_max_concurrent_requests: Option<usize>,
+    /// Separate limit for background refreshes, which otherwise share `max_concurrent_requests`
+    pub refresh_max_concurrent_requests: Option<usize>,
+    /// Number of times a rate-limited (429) request is retried internally, 0 disables retries
+    pub rate_limit_retries: u32,
+    /// Upper bound on how long to wait for a `Retry-After` delay before retrying
+    pub max_rate_limit_wait: Duration,
+    /// Cap on internal retries relative to requests sent, unlimited when unset
+    pub retry_budget: Option<RetryBudget>,
+    /// Outbound HTTP(S) proxy (not supported on wasm32)
+    pub proxy: Option<ProxyConfig>,
+    /// Namespaces served by other registries than `endpoint_url`
+    pub routes: Vec<RegistryRoute>,
+    /// Maximum idle pooled connections kept per host (not supported on wasm32)
+    pub pool_max_idle_per_host: usize,
+    /// How long an idle pooled connection is kept, `None` keeps it indefinitely
+    pub pool_idle_timeout: Option<Duration>,
+    /// TCP keep-alive interval for pooled connections, `None` disables keep-alive probes
+    pub tcp_keepalive: Option<Duration>,
+    /// Maximum number of names sent in a single batch request
+    pub max_batch_size: usize,
+    /// Number of times the failed chunks of a batch are retried, 0 disables retries
+    pub batch_retries: u32,
+    /// Overall time budget for a batch resolution including retries
+    pub batch_deadline: Duration,
+    /// Use the pre-`v1` `/resolve/...` endpoints instead of the `v1` ones
+    pub legacy_batch_endpoint: bool,
 }
 
 impl Default for MvrConfig {
     fn default() -> Self {
         Self {
             endpoint_url: "https://testnet.mvr.mystenlabs.com".to_string(),
+            network_name: None,
             cache_ttl: Duration::from_secs(3600), // 1 hour
+            package_cache_ttl: None,
+            type_cache_ttl: None,
+            cache_ttl_jitter_percent: 0,
+            cache_max_entries: 1000,
+            cache_max_bytes: None,
+            cache_persist_path: None,
+            refresh_ahead_percent: 0,
+            #[cfg(feature = "testing")]
+            fixtures: None,
             overrides: None,
+            sui_rpc_url: Some("https://fullnode.testnet.sui.io:443".to_string()),
+            app_name: None,
+            name_suggestions: false,
+            normalize_names: true,
+            aliases: HashMap::new(),
             timeout: Duration::from_secs(30),
             max_concurrent_requests: 10,
+            reserved_package_permits: 0,
+            batch_max_concurrent_requests: None,
+            refresh_max_concurrent_requests: None,
+            rate_limit_retries: 0,
+            max_rate_limit_wait: Duration::from_secs(30),
+            retry_budget: None,
+            proxy: None,
+            routes: Vec::new(),
+            // Same defaults as reqwest
+            pool_max_idle_per_host: usize::MAX,
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            tcp_keepalive: None,
+            max_batch_size: 50,
+            batch_retries: 0,
+            batch_deadline: Duration::from_secs(30),
+            legacy_batch_endpoint: false,
         }
     }
 }
@@ -34,6 +136,8 @@ impl MvrConfig {
     pub fn mainnet() -> Self {
         Self {
             endpoint_url: "https://mainnet.mvr.mystenlabs.com".to_string(),
+            network_name: Some("mainnet".to_string()),
+            sui_rpc_url: Some("https://fullnode.mainnet.sui.io:443".to_string()),
             ..Default::default()
         }
     }
@@ -42,22 +146,211 @@ impl MvrConfig {
     pub fn testnet() -> Self {
         Self {
             endpoint_url: "https://testnet.mvr.mystenlabs.com".to_string(),
+            network_name: Some("testnet".to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Create a configuration for a registry listening on localhost
+    pub fn localnet(port: u16) -> Self {
+        Self {
+            endpoint_url: format!("http://127.0.0.1:{port}"),
+            network_name: Some("localnet".to_string()),
+            sui_rpc_url: Some("http://127.0.0.1:9000".to_string()),
             ..Default::default()
         }
     }
 
+    /// Tune this configuration for short-lived serverless functions
+    ///
+    /// Requests time out after 5 seconds without internal retries, and the cache keeps
+    /// at most 256 entries that are never refreshed ahead of expiry, so nothing runs
+    /// between invocations. The cache is persisted at `persist_path` (e.g. under
+    /// `/tmp`): a cold start restores it in [`crate::MvrResolver::new`], and calling
+    /// [`crate::MvrResolver::shutdown`] before an invocation returns writes it back.
+    pub fn serverless(self, persist_path: impl Into<PathBuf>) -> Self {
+        Self {
+            timeout: Duration::from_secs(5),
+            cache_max_entries: 256,
+            refresh_ahead_percent: 0,
+            rate_limit_retries: 0,
+            batch_retries: 0,
+            cache_persist_path: Some(persist_path.into()),
+            ..self
+        }
+    }
+
+    /// Create a configuration for an ephemeral local registry discovered from the environment
+    ///
+    /// Reads [`LOCAL_ENDPOINT_ENV`] first, then the file named by
+    /// [`LOCAL_ENDPOINT_FILE_ENV`]. Either may contain a full URL or just a port.
+    pub fn localnet_from_env() -> MvrResult<Self> {
+        Self::localnet_from_lookup(|key| std::env::var(key).ok())
+    }
+
+    fn localnet_from_lookup(lookup: impl Fn(&str) -> Option<String>) -> MvrResult<Self> {
+        let endpoint = match lookup(LOCAL_ENDPOINT_ENV) {
+            Some(endpoint) => endpoint,
+            None => {
+                let path = lookup(LOCAL_ENDPOINT_FILE_ENV).ok_or_else(|| {
+                    MvrError::ConfigError(format!(
+                        "local registry not found: set {LOCAL_ENDPOINT_ENV} or {LOCAL_ENDPOINT_FILE_ENV}"
+                    ))
+                })?;
+                std::fs::read_to_string(&path).map_err(|e| {
+                    MvrError::ConfigError(format!(
+                        "failed to read local registry endpoint from '{path}': {e}"
+                    ))
+                })?
+            }
+        };
+
+        let endpoint = endpoint.trim();
+        let config = match endpoint.parse::<u16>() {
+            Ok(port) => Self::localnet(port),
+            // A full URL keeps the other localnet settings, such as the Sui RPC URL
+            Err(_) => Self::localnet(0).with_endpoint(endpoint.trim_end_matches('/').to_string()),
+        };
+
+        config.validate()?;
+        Ok(config)
+    }
+
     /// Set custom endpoint URL
     pub fn with_endpoint(mut self, endpoint_url: String) -> Self {
         self.endpoint_url = endpoint_url;
         self
     }
 
+    /// Set the name of the network the registry serves, as reported in logs
+    pub fn with_network_name(mut self, network_name: impl Into<String>) -> Self {
+        self.network_name = Some(network_name.into());
+        self
+    }
+
+    /// Set the Sui fullnode JSON-RPC URL used to inspect resolved packages
+    pub fn with_sui_rpc_url(mut self, sui_rpc_url: impl Into<String>) -> Self {
+        self.sui_rpc_url = Some(sui_rpc_url.into());
+        self
+    }
+
+    /// Identify the application in the `User-Agent` of every request
+    ///
+    /// The header becomes `sui-mvr-rust/<version> <app_name>`, e.g. with
+    /// `my-indexer/2.1`, so registry operators can tell traffic apart. Ignored with
+    /// [`crate::MvrResolver::with_http_client`], whose client sets its own header.
+    pub fn with_app_name(mut self, app_name: impl Into<String>) -> Self {
+        self.app_name = Some(app_name.into());
+        self
+    }
+
+    /// Suggest similar names in [`MvrError::PackageNotFound`]
+    ///
+    /// When the registry does not know a package, the resolver searches it for the
+    /// name and compares the results, the overrides and cached names against the
+    /// missing one, so the error can read "did you mean `@suifrens/core`?". Costs one
+    /// extra request per unknown name.
+    pub fn with_name_suggestions(mut self, enabled: bool) -> Self {
+        self.name_suggestions = enabled;
+        self
+    }
+
+    /// Normalize package and type names before validating and resolving them
+    ///
+    /// Enabled by default: surrounding whitespace and a trailing `/` are removed and
+    /// the package part is lowercased, so `" @Suifrens/Core "` resolves as
+    /// `@suifrens/core`. Override and per-call pin names are normalized the same way.
+    /// Disable it for a strict mode in which such names fail with
+    /// [`MvrError::InvalidPackageName`].
+    pub fn with_name_normalization(mut self, enabled: bool) -> Self {
+        self.normalize_names = enabled;
+        self
+    }
+
     /// Set cache TTL
     pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
         self.cache_ttl = ttl;
         self
     }
 
+    /// Set the TTL for cached package addresses, overriding `cache_ttl`
+    pub fn with_package_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.package_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Set the TTL for cached type signatures, overriding `cache_ttl`
+    ///
+    /// Type signatures effectively never change, so this can usually be much longer
+    /// than the package TTL.
+    pub fn with_type_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.type_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Randomly lengthen or shorten each cache entry's TTL by up to `percent`%
+    ///
+    /// Entries populated together, e.g. by a warm-up, would otherwise all expire at
+    /// the same instant and refresh in one burst against the registry.
+    pub fn with_cache_ttl_jitter(mut self, percent: u8) -> Self {
+        self.cache_ttl_jitter_percent = percent;
+        self
+    }
+
+    /// Limit the cache to `max_entries` resolutions
+    pub fn with_cache_max_entries(mut self, max_entries: usize) -> Self {
+        self.cache_max_entries = max_entries;
+        self
+    }
+
+    /// Limit the cache to about `max_bytes` of memory
+    ///
+    /// Entry sizes are estimated from key and value lengths plus bookkeeping; least
+    /// recently used entries are evicted to stay under both this and the entry limit.
+    pub fn with_cache_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.cache_max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Persist the cache across restarts in the snapshot file at `path`
+    ///
+    /// [`crate::MvrResolver::new`] restores the cache from the file when it exists,
+    /// and [`crate::MvrResolver::shutdown`] writes it back.
+    pub fn with_cache_persist_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache_persist_path = Some(path.into());
+        self
+    }
+
+    /// Refresh entries read in the last `percent`% of their TTL in the background
+    ///
+    /// A hit on an entry that is about to expire still returns the cached value, but
+    /// also starts a registry request that replaces it, so hot names never fall out
+    /// of the cache and cause a latency spike. Requires a tokio runtime; ignored on
+    /// wasm32 and without the `network` feature.
+    pub fn with_refresh_ahead(mut self, percent: u8) -> Self {
+        self.refresh_ahead_percent = percent;
+        self
+    }
+
+    /// Record registry responses to, or replay them from, fixture files
+    ///
+    /// See [`crate::testing::FixtureMode`].
+    #[cfg(feature = "testing")]
+    pub fn with_fixtures(mut self, fixtures: crate::testing::FixtureMode) -> Self {
+        self.fixtures = Some(fixtures);
+        self
+    }
+
+    /// Effective TTL for cached package addresses
+    pub fn package_ttl(&self) -> Duration {
+        self.package_cache_ttl.unwrap_or(self.cache_ttl)
+    }
+
+    /// Effective TTL for cached type signatures
+    pub fn type_ttl(&self) -> Duration {
+        self.type_cache_ttl.unwrap_or(self.cache_ttl)
+    }
+
     /// Set request timeout
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
         self.timeout = timeout;
@@ -69,14 +362,581 @@ impl MvrConfig {
         self.overrides = Some(overrides);
         self
     }
+
+    /// Answer names pinned in `lockfile` without the registry
+    ///
+    /// Locked values are added to the static overrides; explicit overrides already
+    /// set for a name take precedence.
+    pub fn with_lockfile(mut self, lockfile: &crate::lockfile::MvrLockfile) -> Self {
+        let locked = lockfile.to_overrides();
+        let overrides = self.overrides.get_or_insert_with(MvrOverrides::new);
+        for (name, address) in locked.packages {
+            overrides.packages.entry(name).or_insert(address);
+        }
+        for (name, type_signature) in locked.types {
+            overrides.types.entry(name).or_insert(type_signature);
+        }
+        self
+    }
+
+    /// Reserve part of the concurrency limit for package lookups
+    ///
+    /// Package resolutions sit on the critical path of transaction building, while
+    /// type resolutions tend to arrive in large bursts. Up to `permits` of the
+    /// `max_concurrent_requests` slots are held back for packages so such bursts
+    /// cannot starve them; packages still use the shared slots when those are free.
+    pub fn with_reserved_package_permits(mut self, permits: usize) -> Self {
+        self.reserved_package_permits = permits;
+        self
+    }
+
+    /// Give batch requests their own pool of `limit` concurrent requests
+    ///
+    /// By default batch chunks compete with single resolutions for the
+    /// `max_concurrent_requests` slots, so a large batch job can delay interactive
+    /// lookups. With a separate limit the two no longer share slots.
+    pub fn with_batch_concurrency(mut self, limit: usize) -> Self {
+        self.batch_max_concurrent_requests = Some(limit);
+        self
+    }
+
+    /// Give background refreshes their own pool of `limit` concurrent requests
+    ///
+    /// Covers refresh-ahead and [`crate::MvrResolver::spawn_cache_maintenance`], see
+    /// [`MvrConfig::with_batch_concurrency`].
+    pub fn with_refresh_concurrency(mut self, limit: usize) -> Self {
+        self.refresh_max_concurrent_requests = Some(limit);
+        self
+    }
+
+    /// Retry rate-limited requests internally
+    ///
+    /// On a 429 the resolver waits for the advertised `Retry-After` delay, capped at
+    /// `max_wait`, and retries up to `max_retries` times before returning
+    /// `MvrError::RateLimitExceeded`.
+    pub fn with_rate_limit_retry(mut self, max_retries: u32, max_wait: Duration) -> Self {
+        self.rate_limit_retries = max_retries;
+        self.max_rate_limit_wait = max_wait;
+        self
+    }
+
+    /// Limit internal retries to a share of the requests sent, see [`RetryBudget`]
+    pub fn with_retry_budget(mut self, budget: RetryBudget) -> Self {
+        self.retry_budget = Some(budget);
+        self
+    }
+
+    /// Route registry requests through an HTTP(S) proxy
+    pub fn with_proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Tune connection reuse for high-throughput resolvers
+    ///
+    /// Keeps at most `max_idle_per_host` idle connections to the registry and closes
+    /// each after `idle_timeout` (`None` keeps them until the server closes them).
+    pub fn with_connection_pool(
+        mut self,
+        max_idle_per_host: usize,
+        idle_timeout: Option<Duration>,
+    ) -> Self {
+        self.pool_max_idle_per_host = max_idle_per_host;
+        self.pool_idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Send TCP keep-alive probes on pooled connections every `interval`
+    pub fn with_tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive = Some(interval);
+        self
+    }
+
+    /// Split batch resolutions into requests of at most `size` names
+    pub fn with_max_batch_size(mut self, size: usize) -> Self {
+        self.max_batch_size = size;
+        self
+    }
+
+    /// Retry the failed chunks of a batch resolution
+    ///
+    /// When some chunks fail with a retryable error, only the names in those chunks
+    /// are requested again, up to `max_retries` times and as long as the next attempt
+    /// can start before `deadline` has elapsed since the batch began.
+    pub fn with_batch_retry(mut self, max_retries: u32, deadline: Duration) -> Self {
+        self.batch_retries = max_retries;
+        self.batch_deadline = deadline;
+        self
+    }
+
+    /// Send resolutions to the legacy pre-`v1` endpoints
+    ///
+    /// By default packages are looked up at `GET /v1/resolution/{name}` and in batches at
+    /// `POST /v1/resolution/bulk`, types at `GET /v1/struct-definition/{name}` and
+    /// `POST /v1/struct-definition/bulk`, as served by the public registry. Enable this
+    /// for self-hosted registries that only implement the older `GET /resolve/package/{name}`,
+    /// `GET /resolve/type/{name}` and combined `POST /resolve/batch` endpoints.
+    pub fn with_legacy_batch_endpoint(mut self, enabled: bool) -> Self {
+        self.legacy_batch_endpoint = enabled;
+        self
+    }
+
+    /// Accept `alias` wherever `package_name` is accepted
+    ///
+    /// `with_alias("core", "@suifrens/core")` makes `resolve_package("core")` and
+    /// `resolve_type("core::suifren::SuiFren")` resolve the full name. Aliases are local
+    /// to this configuration and never sent to the registry; registering an alias again
+    /// replaces its target.
+    pub fn with_alias(mut self, alias: impl Into<String>, package_name: impl Into<String>) -> Self {
+        self.aliases.insert(alias.into(), package_name.into());
+        self
+    }
+
+    /// Resolve the names of one namespace against another registry
+    ///
+    /// Everything not matched by a route keeps using `endpoint_url`, e.g. route
+    /// `@internal` to a private registry while the rest goes to public MVR.
+    pub fn with_route(mut self, route: RegistryRoute) -> Self {
+        self.routes.push(route);
+        self
+    }
+
+    /// Check that the configuration is usable before building a resolver
+    ///
+    /// The endpoint must be an absolute `http`/`https` URL with a host and no
+    /// query, fragment or trailing slash, since request paths are appended to it.
+    /// The same applies to the endpoint of every route.
+    pub fn validate(&self) -> MvrResult<()> {
+        validate_endpoint_url(&self.endpoint_url)?;
+
+        for (i, route) in self.routes.iter().enumerate() {
+            let namespace = &route.namespace;
+            if !namespace.starts_with('@') || namespace.len() < 2 || namespace.contains('/') {
+                return Err(MvrError::ConfigError(format!(
+                    "route namespace '{namespace}' must look like '@namespace'"
+                )));
+            }
+            if self.routes[..i].iter().any(|r| &r.namespace == namespace) {
+                return Err(MvrError::ConfigError(format!(
+                    "namespace '{namespace}' is routed more than once"
+                )));
+            }
+            validate_endpoint_url(&route.endpoint_url)?;
+        }
+
+        for (alias, package_name) in &self.aliases {
+            if alias.is_empty()
+                || alias.starts_with('@')
+                || alias.contains("::")
+                || alias.chars().any(char::is_whitespace)
+            {
+                return Err(MvrError::ConfigError(format!(
+                    "alias '{alias}' must be non-empty, without '@', '::' or whitespace"
+                )));
+            }
+            crate::error::validate_package_name(package_name).map_err(|_| {
+                MvrError::ConfigError(format!(
+                    "alias '{alias}' points at invalid package name '{package_name}'"
+                ))
+            })?;
+        }
+
+        if let Some(sui_rpc_url) = &self.sui_rpc_url {
+            validate_endpoint_url(sui_rpc_url)?;
+        }
+
+        if let Some(app_name) = &self.app_name {
+            if app_name.is_empty() || !app_name.chars().all(|c| c.is_ascii_graphic() || c == ' ') {
+                return Err(MvrError::ConfigError(format!(
+                    "app name '{app_name}' must be non-empty printable ASCII"
+                )));
+            }
+        }
+
+        if let Some(proxy) = &self.proxy {
+            match url::Url::parse(&proxy.url) {
+                Ok(url) if matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") => {}
+                Ok(url) => {
+                    return Err(MvrError::ConfigError(format!(
+                        "proxy URL '{}' uses unsupported scheme '{}'",
+                        proxy.url,
+                        url.scheme()
+                    )))
+                }
+                Err(e) => {
+                    return Err(MvrError::ConfigError(format!(
+                        "proxy URL '{}' is not a valid URL ({e})",
+                        proxy.url
+                    )))
+                }
+            }
+        }
+
+        if self.timeout.is_zero() {
+            return Err(MvrError::ConfigError(
+                "timeout must be greater than zero".to_string(),
+            ));
+        }
+        if self.max_concurrent_requests == 0 {
+            return Err(MvrError::ConfigError(
+                "max_concurrent_requests must be at least 1".to_string(),
+            ));
+        }
+        if self.reserved_package_permits >= self.max_concurrent_requests {
+            return Err(MvrError::ConfigError(
+                "reserved_package_permits must leave at least one request slot for types"
+                    .to_string(),
+            ));
+        }
+        if self.batch_max_concurrent_requests == Some(0) {
+            return Err(MvrError::ConfigError(
+                "batch_max_concurrent_requests must be at least 1".to_string(),
+            ));
+        }
+        if self.refresh_max_concurrent_requests == Some(0) {
+            return Err(MvrError::ConfigError(
+                "refresh_max_concurrent_requests must be at least 1".to_string(),
+            ));
+        }
+        if let Some(budget) = &self.retry_budget {
+            if !(0.0..=1.0).contains(&budget.ratio) || budget.window.is_zero() {
+                return Err(MvrError::ConfigError(
+                    "retry budget ratio must be between 0 and 1 over a non-zero window".to_string(),
+                ));
+            }
+        }
+        if self.cache_ttl_jitter_percent > 100 {
+            return Err(MvrError::ConfigError(
+                "cache_ttl_jitter_percent must be at most 100".to_string(),
+            ));
+        }
+        if self.refresh_ahead_percent > 100 {
+            return Err(MvrError::ConfigError(
+                "refresh_ahead_percent must be at most 100".to_string(),
+            ));
+        }
+        if self.max_batch_size == 0 {
+            return Err(MvrError::ConfigError(
+                "max_batch_size must be at least 1".to_string(),
+            ));
+        }
+        if self.cache_max_entries == 0 || self.cache_max_bytes == Some(0) {
+            return Err(MvrError::ConfigError(
+                "cache limits must be at least 1".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Options for a single resolution call, see [`crate::MvrResolver::resolve_package_with`]
+#[derive(Debug, Clone, Default)]
+pub struct ResolveOptions {
+    /// Overrides that take precedence over the resolver's own, for this call only
+    pub extra_overrides: Option<MvrOverrides>,
+}
+
+impl ResolveOptions {
+    /// Options that resolve exactly like the plain `resolve_*` methods
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pin names for this call only, e.g. a tenant-specific package version
+    pub fn with_extra_overrides(mut self, overrides: MvrOverrides) -> Self {
+        self.extra_overrides = Some(overrides);
+        self
+    }
+
+    /// Pinned address of `name`, matching pins by their normalized name if `normalize`
+    pub(crate) fn package(&self, name: &str, normalize: bool) -> Option<&String> {
+        let packages = &self.extra_overrides.as_ref()?.packages;
+        packages.get(name).or_else(|| {
+            let (_, address) = packages
+                .iter()
+                .find(|(pinned, _)| normalize && normalize_package_name(pinned) == name)?;
+            Some(address)
+        })
+    }
+
+    /// Pinned signature of `name`, see [`ResolveOptions::package`]
+    pub(crate) fn type_signature(&self, name: &str, normalize: bool) -> Option<&String> {
+        let types = &self.extra_overrides.as_ref()?.types;
+        types.get(name).or_else(|| {
+            let (_, type_sig) = types
+                .iter()
+                .find(|(pinned, _)| normalize && normalize_type_name(pinned) == name)?;
+            Some(type_sig)
+        })
+    }
+}
+
+/// Where a resolved value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionSource {
+    /// Static override
+    Override,
+    /// In-memory cache
+    Cache,
+    /// MVR registry request
+    Network,
+}
+
+/// A resolved value with details about how it was obtained
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolution {
+    /// Package address or type signature
+    pub value: Arc<str>,
+    /// Where the value came from
+    pub source: ResolutionSource,
+    /// Registry data version (ETag) the value was resolved against, if the registry
+    /// reported one
+    pub resolved_against_version: Option<String>,
+}
+
+impl Resolution {
+    pub(crate) fn new(
+        value: Arc<str>,
+        source: ResolutionSource,
+        resolved_against_version: Option<String>,
+    ) -> Self {
+        Self {
+            value,
+            source,
+            resolved_against_version,
+        }
+    }
+}
+
+/// Check that `endpoint` can have request paths appended to it
+fn validate_endpoint_url(endpoint: &str) -> MvrResult<()> {
+    let invalid = |reason: &str| {
+        Err(MvrError::ConfigError(format!(
+            "endpoint URL '{endpoint}' {reason}"
+        )))
+    };
+
+    if endpoint.trim() != endpoint {
+        return invalid("must not contain leading or trailing whitespace");
+    }
+
+    let url = match url::Url::parse(endpoint) {
+        Ok(url) => url,
+        Err(e) => return invalid(&format!("is not a valid URL ({e})")),
+    };
+
+    if !matches!(url.scheme(), "http" | "https") {
+        return invalid(&format!(
+            "uses unsupported scheme '{}', expected http or https",
+            url.scheme()
+        ));
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return invalid("has no host");
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return invalid("must not contain a query string or fragment");
+    }
+    if endpoint.ends_with('/') {
+        return invalid("must not end with '/'");
+    }
+
+    Ok(())
+}
+
+/// Routes one namespace to its own registry, e.g. `@internal` to a corporate registry
+#[derive(Clone, PartialEq, Eq)]
+pub struct RegistryRoute {
+    /// Namespace the route applies to, e.g. `@internal` for `@internal/*`
+    pub namespace: String,
+    /// Registry endpoint serving the namespace
+    pub endpoint_url: String,
+    /// Bearer token sent only to this registry
+    pub auth_token: Option<String>,
+}
+
+impl RegistryRoute {
+    /// Resolve names in `namespace` against `endpoint_url`
+    pub fn new(namespace: String, endpoint_url: String) -> Self {
+        Self {
+            namespace,
+            endpoint_url,
+            auth_token: None,
+        }
+    }
+
+    /// Authenticate requests to this registry with a bearer token
+    pub fn with_auth_token(mut self, token: String) -> Self {
+        self.auth_token = Some(token);
+        self
+    }
+}
+
+// Keep tokens out of logs
+impl std::fmt::Debug for RegistryRoute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegistryRoute")
+            .field("namespace", &self.namespace)
+            .field("endpoint_url", &self.endpoint_url)
+            .field(
+                "auth_token",
+                &self.auth_token.as_ref().map(|_| "<redacted>"),
+            )
+            .finish()
+    }
+}
+
+/// Request outcomes observed for one registry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RegistryHealth {
+    /// Routed namespace, `None` for the default registry
+    pub namespace: Option<String>,
+    pub endpoint_url: String,
+    /// Requests the registry answered, including "not found"
+    pub successes: u64,
+    /// Requests that failed with a transport, server or rate-limit error
+    pub failures: u64,
+    /// Failures since the last success
+    pub consecutive_failures: u64,
+    /// Message of the most recent failure
+    pub last_error: Option<String>,
+}
+
+impl RegistryHealth {
+    /// Whether the most recent request to the registry succeeded (or none was made)
+    pub fn is_healthy(&self) -> bool {
+        self.consecutive_failures == 0
+    }
+}
+
+/// Rate-limit headers of the latest registry or fullnode response that sent them
+///
+/// Read with [`crate::MvrResolver::rate_limit_info`] to slow down before the registry
+/// starts answering 429. Header values that are missing or not numbers are `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    /// Requests allowed per window, from `x-ratelimit-limit`
+    pub limit: Option<u64>,
+    /// Requests left in the current window, from `x-ratelimit-remaining`
+    pub remaining: Option<u64>,
+    /// Time until the window resets when the response arrived, from `x-ratelimit-reset`
+    /// in seconds or as a Unix timestamp
+    pub reset_after: Option<Duration>,
+    /// Requested wait from the `retry-after` header of a 429 response
+    pub retry_after: Option<Duration>,
+    /// When the response arrived
+    pub observed_at: crate::Instant,
+}
+
+impl RateLimitInfo {
+    /// Time left until the window resets, zero once it has
+    pub fn reset_in(&self) -> Option<Duration> {
+        self.reset_after
+            .map(|reset_after| reset_after.saturating_sub(self.observed_at.elapsed()))
+    }
+
+    /// Whether the window had no requests left and has not reset since
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == Some(0) && self.reset_in().is_none_or(|left| !left.is_zero())
+    }
+}
+
+/// Cap on internal retries, shared by every caller of a resolver
+///
+/// During a registry outage every failing request would otherwise be retried, so
+/// many callers multiply the load on a registry that is already struggling. With a
+/// budget, at most `ratio` of the requests sent in each `window` may be retries
+/// (with at least `min_retries` allowed per window, so quiet resolvers can still
+/// retry). Rate-limit and batch retries beyond the budget fail fast with
+/// [`MvrError::RetryBudgetExhausted`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryBudget {
+    /// Share of requests that may be retries, between 0 and 1
+    pub ratio: f64,
+    /// Period over which requests and retries are counted
+    pub window: Duration,
+    /// Retries allowed per window regardless of `ratio`
+    pub min_retries: u32,
+}
+
+impl RetryBudget {
+    /// Allow retries for `ratio` of the requests sent in each `window`, and at least 10
+    pub fn new(ratio: f64, window: Duration) -> Self {
+        Self {
+            ratio,
+            window,
+            min_retries: 10,
+        }
+    }
+
+    /// Retries allowed per window regardless of the ratio
+    pub fn with_min_retries(mut self, min_retries: u32) -> Self {
+        self.min_retries = min_retries;
+        self
+    }
+}
+
+/// Outbound proxy settings for registry requests
+#[derive(Clone, PartialEq, Eq)]
+pub struct ProxyConfig {
+    /// Proxy URL, e.g. `http://proxy.corp.example:3128`
+    pub url: String,
+    /// Hosts, domains or IP ranges that bypass the proxy
+    pub no_proxy: Vec<String>,
+    /// Basic auth credentials as `(username, password)`
+    pub basic_auth: Option<(String, String)>,
+}
+
+impl ProxyConfig {
+    /// Proxy all registry requests through `url`
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            no_proxy: Vec::new(),
+            basic_auth: None,
+        }
+    }
+
+    /// Authenticate to the proxy with basic auth
+    pub fn with_basic_auth(mut self, username: String, password: String) -> Self {
+        self.basic_auth = Some((username, password));
+        self
+    }
+
+    /// Bypass the proxy for the given hosts, domains (e.g. `.corp.example`) or IP ranges
+    pub fn with_no_proxy(mut self, hosts: Vec<String>) -> Self {
+        self.no_proxy = hosts;
+        self
+    }
+}
+
+// Keep the proxy password out of logs
+impl std::fmt::Debug for ProxyConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProxyConfig")
+            .field("url", &self.url)
+            .field("no_proxy", &self.no_proxy)
+            .field(
+                "basic_auth",
+                &self
+                    .basic_auth
+                    .as_ref()
+                    .map(|(user, _)| (user, "<redacted>")),
+            )
+            .finish()
+    }
 }
 
 /// Static overrides for package addresses and types
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct MvrOverrides {
     /// Map of package names to their addresses
+    #[serde(serialize_with = "serialize_sorted")]
     pub packages: HashMap<String, String>,
     /// Map of type names to their full signatures
+    #[serde(serialize_with = "serialize_sorted")]
     pub types: HashMap<String, String>,
 }
 
@@ -99,61 +959,421 @@ impl MvrOverrides {
     }
 
     /// Load overrides from a JSON file
+    ///
+    /// Files written by 0.1.x carry no `version` field and may omit either map;
+    /// they are upgraded transparently and a warning is logged. Files from a newer
+    /// schema than [`OVERRIDES_SCHEMA_VERSION`] are rejected.
     pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
-        serde_json::from_str(json)
+        let file: OverridesFile = serde_json::from_str(json)?;
+        match file.version {
+            None => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    packages = file.packages.len(),
+                    types = file.types.len(),
+                    "upgrading unversioned 0.1.x override file; re-save it with to_json"
+                );
+            }
+            Some(version) if version > OVERRIDES_SCHEMA_VERSION => {
+                return Err(serde::de::Error::custom(format!(
+                    "override file schema version {version} is newer than supported version {OVERRIDES_SCHEMA_VERSION}"
+                )));
+            }
+            Some(_) => {}
+        }
+        Ok(Self {
+            packages: file.packages,
+            types: file.types,
+        })
     }
 
     /// Save overrides to JSON format
+    ///
+    /// Keys are emitted in sorted order, so the same overrides always produce
+    /// byte-identical output regardless of insertion order. The output is tagged
+    /// with [`OVERRIDES_SCHEMA_VERSION`].
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
-        serde_json::to_string_pretty(self)
+        serde_json::to_string_pretty(&VersionedOverrides {
+            version: OVERRIDES_SCHEMA_VERSION,
+            overrides: self,
+        })
+    }
+}
+
+/// Schema version written by [`MvrOverrides::to_json`]
+///
+/// Unversioned files are treated as the 0.1.x format.
+pub const OVERRIDES_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct VersionedOverrides<'a> {
+    version: u32,
+    #[serde(flatten)]
+    overrides: &'a MvrOverrides,
+}
+
+// Accepts every known on-disk layout of the override file
+#[derive(Deserialize)]
+struct OverridesFile {
+    version: Option<u32>,
+    #[serde(default)]
+    packages: HashMap<String, String>,
+    #[serde(default)]
+    types: HashMap<String, String>,
+}
+
+/// Report of which names are served by static overrides
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OverrideCoverage {
+    /// Names resolved from overrides
+    pub covered: Vec<String>,
+    /// Names that would require network resolution
+    pub uncovered: Vec<String>,
+}
+
+impl OverrideCoverage {
+    /// Check whether every name is covered by overrides
+    pub fn is_complete(&self) -> bool {
+        self.uncovered.is_empty()
+    }
+
+    /// Fraction of names covered by overrides (1.0 for an empty name list)
+    pub fn ratio(&self) -> f64 {
+        let total = self.covered.len() + self.uncovered.len();
+        if total == 0 {
+            1.0
+        } else {
+            self.covered.len() as f64 / total as f64
+        }
+    }
+}
+
+/// Registration state of a package name, see [`crate::MvrResolver::check_name_availability`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum NameAvailability {
+    /// Neither the name nor its namespace is registered
+    Available,
+    /// The name itself is already registered
+    Registered { name: String, owner: String },
+    /// The name is free but its namespace belongs to `owner`
+    NamespaceReserved { namespace: String, owner: String },
+}
+
+impl NameAvailability {
+    /// Whether `publisher` could register the name
+    ///
+    /// A reserved namespace only blocks publishers other than its owner.
+    pub fn is_available_to(&self, publisher: &str) -> bool {
+        match self {
+            NameAvailability::Available => true,
+            NameAvailability::Registered { .. } => false,
+            NameAvailability::NamespaceReserved { owner, .. } => {
+                owner.eq_ignore_ascii_case(publisher)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for NameAvailability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NameAvailability::Available => write!(f, "name is available"),
+            NameAvailability::Registered { name, owner } => {
+                write!(f, "'{name}' is already registered by {owner}")
+            }
+            NameAvailability::NamespaceReserved { namespace, owner } => {
+                write!(f, "namespace '{namespace}' is reserved by {owner}")
+            }
+        }
+    }
+}
+
+/// Registry record of an MVR application, see [`crate::MvrResolver::resolve_app`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppRecord {
+    /// Registered name, e.g. `@suifrens/core`
+    #[serde(default)]
+    pub name: String,
+    /// Address the name currently points to
+    #[serde(default, alias = "package_address")]
+    pub app_address: Option<String>,
+    /// Account that registered the name
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Package address of the app on each network, keyed by network name
+    #[serde(default)]
+    pub packages: BTreeMap<String, String>,
+    /// Descriptive metadata and external links
+    #[serde(default)]
+    pub metadata: AppMetadata,
+    /// Names of the packages the app depends on
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+}
+
+/// Descriptive metadata of an [`AppRecord`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub documentation_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contact: Option<String>,
+    /// Any other metadata the registry returns
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+/// Addresses of an upgraded package, see [`crate::MvrResolver::resolve_package_versions`]
+///
+/// Move calls should target `latest_address`, while type tags use the address of
+/// the package version that defined the type, which is `original_address` for types
+/// declared in the first version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackageVersions {
+    /// Package name
+    pub name: String,
+    /// Address of the latest version
+    pub latest_address: String,
+    /// Address of the first version, the package's original ID
+    pub original_address: String,
+    /// Version number of the latest version, starting at 1
+    pub version: u64,
+}
+
+impl PackageVersions {
+    /// Whether the package has been upgraded since it was first published
+    pub fn is_upgraded(&self) -> bool {
+        self.version > 1
+    }
+}
+
+/// On-chain Display template of a type, see [`crate::MvrResolver::get_type_display`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TypeDisplay {
+    /// Display version the template was published at
+    pub version: u64,
+    /// Template of each field, e.g. `name` → `{name}`
+    pub fields: BTreeMap<String, String>,
+}
+
+impl TypeDisplay {
+    /// Template of a field by name
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(String::as_str)
+    }
+
+    /// `name` template
+    pub fn name(&self) -> Option<&str> {
+        self.field("name")
+    }
+
+    /// `description` template
+    pub fn description(&self) -> Option<&str> {
+        self.field("description")
+    }
+
+    /// `image_url` template
+    pub fn image_url(&self) -> Option<&str> {
+        self.field("image_url")
+    }
+}
+
+/// Dependency closure of a package, see [`crate::MvrResolver::resolve_dependencies`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DependencyGraph {
+    /// Package the walk started from
+    pub root: String,
+    /// Address of every package in the closure, including the root
+    pub packages: BTreeMap<String, String>,
+    /// Direct dependencies declared by each package in the closure
+    pub dependencies: BTreeMap<String, Vec<String>>,
+}
+
+impl DependencyGraph {
+    /// Packages in the closure other than the root
+    pub fn transitive_dependencies(&self) -> impl Iterator<Item = &str> {
+        self.packages
+            .keys()
+            .map(String::as_str)
+            .filter(|name| *name != self.root)
+    }
+}
+
+/// Read-optimized copy of [`MvrOverrides`] held by the resolver
+///
+/// Values are stored as `Arc<str>` so override hits hand out a reference-counted
+/// handle instead of cloning the backing string.
+#[derive(Debug, Default)]
+pub(crate) struct SharedOverrides {
+    packages: HashMap<String, Arc<str>>,
+    types: HashMap<String, Arc<str>>,
+}
+
+impl SharedOverrides {
+    pub fn package(&self, name: &str) -> Option<Arc<str>> {
+        self.packages.get(name).cloned()
+    }
+
+    pub fn type_signature(&self, name: &str) -> Option<Arc<str>> {
+        self.types.get(name).cloned()
+    }
+
+    #[cfg(feature = "network")]
+    pub fn package_names(&self) -> impl Iterator<Item = &str> {
+        self.packages.keys().map(String::as_str)
+    }
+}
+
+impl SharedOverrides {
+    /// Share `overrides`, normalizing their names like lookups do when `normalize` is set
+    ///
+    /// See [`MvrConfig::with_name_normalization`]; without it an override for
+    /// `@MyOrg/core` could never match the normalized `@myorg/core`.
+    pub fn new(overrides: &MvrOverrides, normalize: bool) -> Self {
+        let share = |map: &HashMap<String, String>, key: fn(&str) -> Cow<'_, str>| {
+            map.iter()
+                .map(|(name, value)| {
+                    let name = if normalize {
+                        key(name).into_owned()
+                    } else {
+                        name.clone()
+                    };
+                    (name, Arc::from(value.as_str()))
+                })
+                .collect()
+        };
+
+        Self {
+            packages: share(&overrides.packages, normalize_package_name),
+            types: share(&overrides.types, normalize_type_name),
+        }
     }
 }
 
+/// Serialize a map with its keys in sorted order for stable, diff-friendly output
+pub(crate) fn serialize_sorted<S, V>(
+    map: &HashMap<String, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    V: Serialize,
+{
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
 /// MVR API response structure for package resolution
+///
+/// Unknown fields are ignored so that registry additions don't break parsing.
+#[cfg(feature = "network")]
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)] // These fields are for future API parsing
 pub(crate) struct MvrPackageResponse {
     pub package_id: Option<String>,
     pub address: Option<String>,
-    pub name: Option<String>,
-    pub version: Option<String>,
+}
+
+#[cfg(feature = "network")]
+impl MvrPackageResponse {
+    /// The package address, preferring `address` over the older `package_id`
+    pub fn into_address(self) -> Option<String> {
+        self.address.or(self.package_id)
+    }
 }
 
 /// MVR API response structure for type resolution
+#[cfg(feature = "network")]
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)] // These fields are for future API parsing
 pub(crate) struct MvrTypeResponse {
+    pub type_tag: Option<String>,
     pub type_signature: Option<String>,
-    pub package_id: Option<String>,
-    pub module: Option<String>,
-    pub name: Option<String>,
+    pub signature: Option<String>,
+}
+
+#[cfg(feature = "network")]
+impl MvrTypeResponse {
+    /// The type signature, preferring the `v1` `type_tag` over the older
+    /// `type_signature` and `signature`
+    pub fn into_signature(self) -> Option<String> {
+        self.type_tag.or(self.type_signature).or(self.signature)
+    }
 }
 
-/// Batch resolution request
+/// Registry name search response
+#[cfg(feature = "network")]
+#[derive(Debug, Deserialize)]
+pub(crate) struct NameSearchResponse {
+    #[serde(default)]
+    pub data: Vec<NameSearchHit>,
+}
+
+#[cfg(feature = "network")]
+#[derive(Debug, Deserialize)]
+pub(crate) struct NameSearchHit {
+    pub name: String,
+}
+
+/// Body of `POST /v1/resolution/bulk` (`names`) and `POST /v1/struct-definition/bulk` (`types`)
+#[cfg(feature = "network")]
 #[derive(Debug, Serialize)]
 pub(crate) struct BatchResolutionRequest {
-    pub packages: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub names: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub types: Option<Vec<String>>,
 }
 
-/// Batch resolution response
+/// Response of the bulk endpoints, keyed by the requested name
+///
+/// Names the registry doesn't know are left out of `resolution`.
+#[cfg(feature = "network")]
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)] // Error field is for future error handling
 pub(crate) struct BatchResolutionResponse {
+    #[serde(default)]
+    pub resolution: HashMap<String, BatchResolution>,
+}
+
+/// One entry of a bulk response: `package_id` for names, `type_tag` for types
+#[cfg(feature = "network")]
+#[derive(Debug, Deserialize)]
+pub(crate) struct BatchResolution {
+    pub package_id: Option<String>,
+    pub type_tag: Option<String>,
+}
+
+/// Body of the legacy `POST /resolve/batch` endpoint
+#[cfg(feature = "network")]
+#[derive(Debug, Serialize)]
+pub(crate) struct LegacyBatchRequest {
+    pub packages: Option<Vec<String>>,
+    pub types: Option<Vec<String>>,
+}
+
+/// Response of the legacy `POST /resolve/batch` endpoint
+#[cfg(feature = "network")]
+#[derive(Debug, Deserialize)]
+pub(crate) struct LegacyBatchResponse {
     pub packages: Option<HashMap<String, String>>,
     pub types: Option<HashMap<String, String>>,
-    pub errors: Option<HashMap<String, String>>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::Path;
 
     #[test]
     fn test_mvr_config_defaults() {
         let config = MvrConfig::default();
         assert_eq!(config.cache_ttl, Duration::from_secs(3600));
         assert!(config.endpoint_url.contains("testnet"));
+        assert_eq!(config.rate_limit_retries, 0);
     }
 
     #[test]
@@ -162,6 +1382,22 @@ mod tests {
         assert!(config.endpoint_url.contains("mainnet"));
     }
 
+    #[test]
+    fn test_mvr_config_serverless() {
+        let config = MvrConfig::mainnet()
+            .with_refresh_ahead(20)
+            .serverless("/tmp/mvr-cache.json");
+        assert!(config.endpoint_url.contains("mainnet"));
+        assert_eq!(config.timeout, Duration::from_secs(5));
+        assert_eq!(config.cache_max_entries, 256);
+        assert_eq!(config.refresh_ahead_percent, 0);
+        assert_eq!(
+            config.cache_persist_path.as_deref(),
+            Some(Path::new("/tmp/mvr-cache.json"))
+        );
+        assert!(config.validate().is_ok());
+    }
+
     #[test]
     fn test_mvr_config_builder() {
         let config = MvrConfig::testnet()
@@ -172,6 +1408,232 @@ mod tests {
         assert_eq!(config.timeout, Duration::from_secs(60));
     }
 
+    #[test]
+    fn test_mvr_config_validate() {
+        assert!(MvrConfig::mainnet().validate().is_ok());
+        assert!(MvrConfig::default()
+            .with_endpoint("http://127.0.0.1:9000".to_string())
+            .validate()
+            .is_ok());
+
+        let invalid_endpoints = [
+            "mainnet.mvr.mystenlabs.com",
+            "ftp://mainnet.mvr.mystenlabs.com",
+            "https://",
+            "https://mainnet.mvr.mystenlabs.com/",
+            "https://mainnet.mvr.mystenlabs.com?network=mainnet",
+            " https://mainnet.mvr.mystenlabs.com",
+        ];
+        for endpoint in invalid_endpoints {
+            let result = MvrConfig::default()
+                .with_endpoint(endpoint.to_string())
+                .validate();
+            assert!(
+                matches!(result, Err(MvrError::ConfigError(_))),
+                "Should reject endpoint: {endpoint}"
+            );
+        }
+
+        let config = MvrConfig {
+            max_concurrent_requests: 0,
+            ..MvrConfig::default()
+        };
+        assert!(config.validate().is_err());
+        assert!(MvrConfig::default()
+            .with_max_batch_size(0)
+            .validate()
+            .is_err());
+        assert!(MvrConfig::default()
+            .with_batch_concurrency(0)
+            .validate()
+            .is_err());
+        assert!(MvrConfig::default()
+            .with_refresh_concurrency(0)
+            .validate()
+            .is_err());
+        for budget in [
+            RetryBudget::new(1.5, Duration::from_secs(10)),
+            RetryBudget::new(f64::NAN, Duration::from_secs(10)),
+            RetryBudget::new(0.1, Duration::ZERO),
+        ] {
+            assert!(MvrConfig::default()
+                .with_retry_budget(budget)
+                .validate()
+                .is_err());
+        }
+        assert!(MvrConfig::default()
+            .with_retry_budget(RetryBudget::new(0.1, Duration::from_secs(10)))
+            .validate()
+            .is_ok());
+        for app_name in ["", "indexer\n", "indéxer/1.0"] {
+            assert!(MvrConfig::default()
+                .with_app_name(app_name)
+                .validate()
+                .is_err());
+        }
+        assert!(MvrConfig::default()
+            .with_app_name("my-indexer/2.1 (ops@example.com)")
+            .validate()
+            .is_ok());
+        assert!(MvrConfig::default()
+            .with_reserved_package_permits(10)
+            .validate()
+            .is_err());
+        assert!(MvrConfig::default()
+            .with_cache_ttl_jitter(101)
+            .validate()
+            .is_err());
+        assert!(MvrConfig::default()
+            .with_refresh_ahead(101)
+            .validate()
+            .is_err());
+        assert!(MvrConfig::default()
+            .with_cache_max_entries(0)
+            .validate()
+            .is_err());
+        assert!(MvrConfig::default()
+            .with_cache_max_bytes(0)
+            .validate()
+            .is_err());
+        assert!(MvrConfig::default()
+            .with_cache_max_entries(50_000)
+            .with_cache_max_bytes(16 << 20)
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_registry_routes() {
+        let route = RegistryRoute::new(
+            "@internal".to_string(),
+            "https://mvr.corp.example".to_string(),
+        )
+        .with_auth_token("s3cret".to_string());
+        let config = MvrConfig::mainnet().with_route(route.clone());
+        assert!(config.validate().is_ok());
+        assert!(!format!("{config:?}").contains("s3cret"));
+
+        let invalid_routes = [
+            RegistryRoute::new(
+                "internal".to_string(),
+                "https://mvr.corp.example".to_string(),
+            ),
+            RegistryRoute::new(
+                "@internal/pkg".to_string(),
+                "https://mvr.corp.example".to_string(),
+            ),
+            RegistryRoute::new("@internal".to_string(), "mvr.corp.example".to_string()),
+        ];
+        for invalid in invalid_routes {
+            let result = MvrConfig::mainnet().with_route(invalid.clone()).validate();
+            assert!(
+                matches!(result, Err(MvrError::ConfigError(_))),
+                "Should reject route: {invalid:?}"
+            );
+        }
+
+        let duplicate = MvrConfig::mainnet()
+            .with_route(route.clone())
+            .with_route(route);
+        assert!(duplicate.validate().is_err());
+    }
+
+    #[test]
+    fn test_name_availability() {
+        let reserved = NameAvailability::NamespaceReserved {
+            namespace: "@suifrens".to_string(),
+            owner: "0xABC".to_string(),
+        };
+        assert!(reserved.is_available_to("0xabc"));
+        assert!(!reserved.is_available_to("0xdef"));
+        assert_eq!(
+            reserved.to_string(),
+            "namespace '@suifrens' is reserved by 0xABC"
+        );
+
+        let registered = NameAvailability::Registered {
+            name: "@suifrens/core".to_string(),
+            owner: "0xabc".to_string(),
+        };
+        assert!(!registered.is_available_to("0xabc"));
+        assert!(NameAvailability::Available.is_available_to("0xdef"));
+        assert_eq!(
+            serde_json::to_value(&registered).unwrap()["status"],
+            "registered"
+        );
+    }
+
+    #[test]
+    fn test_proxy_config() {
+        let proxy = ProxyConfig::new("http://proxy.corp.example:3128".to_string())
+            .with_basic_auth("user".to_string(), "hunter2".to_string())
+            .with_no_proxy(vec!["localhost".to_string(), ".corp.example".to_string()]);
+        let config = MvrConfig::mainnet().with_proxy(proxy);
+        assert!(config.validate().is_ok());
+
+        let debug = format!("{:?}", config.proxy.unwrap());
+        assert!(debug.contains("user"));
+        assert!(!debug.contains("hunter2"));
+
+        let config =
+            MvrConfig::mainnet().with_proxy(ProxyConfig::new("ftp://proxy:21".to_string()));
+        assert!(matches!(config.validate(), Err(MvrError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_per_kind_cache_ttl() {
+        let config = MvrConfig::default().with_cache_ttl(Duration::from_secs(60));
+        assert_eq!(config.package_ttl(), Duration::from_secs(60));
+        assert_eq!(config.type_ttl(), Duration::from_secs(60));
+
+        let config = config
+            .with_package_cache_ttl(Duration::from_secs(600))
+            .with_type_cache_ttl(Duration::from_secs(86400));
+        assert_eq!(config.package_ttl(), Duration::from_secs(600));
+        assert_eq!(config.type_ttl(), Duration::from_secs(86400));
+    }
+
+    #[test]
+    fn test_connection_pool_config() {
+        let config = MvrConfig::default();
+        assert_eq!(config.pool_max_idle_per_host, usize::MAX);
+        assert_eq!(config.pool_idle_timeout, Some(Duration::from_secs(90)));
+        assert_eq!(config.tcp_keepalive, None);
+
+        let config = MvrConfig::mainnet()
+            .with_connection_pool(32, None)
+            .with_tcp_keepalive(Duration::from_secs(60));
+        assert_eq!(config.pool_max_idle_per_host, 32);
+        assert_eq!(config.pool_idle_timeout, None);
+        assert_eq!(config.tcp_keepalive, Some(Duration::from_secs(60)));
+        assert!(crate::MvrResolver::try_new(config).is_ok());
+    }
+
+    #[test]
+    fn test_localnet_discovery() {
+        let config = MvrConfig::localnet_from_lookup(|key| {
+            (key == LOCAL_ENDPOINT_ENV).then(|| "41234".to_string())
+        })
+        .unwrap();
+        assert_eq!(config.endpoint_url, "http://127.0.0.1:41234");
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, b"http://localhost:9123/\n").unwrap();
+        let path = file.path().to_string_lossy().to_string();
+        let config = MvrConfig::localnet_from_lookup(|key| {
+            (key == LOCAL_ENDPOINT_FILE_ENV).then(|| path.clone())
+        })
+        .unwrap();
+        assert_eq!(config.endpoint_url, "http://localhost:9123");
+        assert_eq!(config.network_name.as_deref(), Some("localnet"));
+        assert_eq!(config.sui_rpc_url.as_deref(), Some("http://127.0.0.1:9000"));
+
+        assert!(matches!(
+            MvrConfig::localnet_from_lookup(|_| None),
+            Err(MvrError::ConfigError(_))
+        ));
+    }
+
     #[test]
     fn test_mvr_config_clone() {
         let config = MvrConfig::mainnet();
@@ -210,4 +1672,76 @@ mod tests {
 
         assert_eq!(overrides.packages, deserialized.packages);
     }
+
+    #[test]
+    fn test_overrides_json_upgrades_legacy_format() {
+        let json = MvrOverrides::new()
+            .with_package("@test/package".to_string(), "0x123".to_string())
+            .to_json()
+            .unwrap();
+        assert!(json.contains(&format!("\"version\": {OVERRIDES_SCHEMA_VERSION}")));
+
+        // 0.1.x files have no version and may omit the types map
+        let legacy =
+            MvrOverrides::from_json(r#"{"packages": {"@test/package": "0x123"}}"#).unwrap();
+        assert_eq!(legacy.packages["@test/package"], "0x123");
+        assert!(legacy.types.is_empty());
+
+        let legacy =
+            MvrOverrides::from_json(r#"{"packages": {}, "types": {"@a/b::m::T": "0x1::m::T"}}"#)
+                .unwrap();
+        assert_eq!(legacy.types["@a/b::m::T"], "0x1::m::T");
+
+        let future = format!(
+            r#"{{"version": {}, "packages": {{}}, "types": {{}}}}"#,
+            OVERRIDES_SCHEMA_VERSION + 1
+        );
+        let err = MvrOverrides::from_json(&future).unwrap_err();
+        assert!(err.to_string().contains("newer than supported"));
+    }
+
+    #[test]
+    fn test_shared_overrides_hand_out_same_allocation() {
+        let overrides = MvrOverrides::new()
+            .with_package("@test/package".to_string(), "0x123".to_string())
+            .with_type(
+                "@test/package::module::Type".to_string(),
+                "0x123::module::Type".to_string(),
+            );
+        let shared = SharedOverrides::new(&overrides, false);
+
+        let first = shared.package("@test/package").unwrap();
+        let second = shared.package("@test/package").unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(&*first, "0x123");
+
+        assert_eq!(
+            shared
+                .type_signature("@test/package::module::Type")
+                .as_deref(),
+            Some("0x123::module::Type")
+        );
+        assert!(shared.package("@missing/package").is_none());
+    }
+
+    #[test]
+    fn test_overrides_json_is_deterministic() {
+        let names = ["@b/pkg", "@a/pkg", "@d/pkg", "@c/pkg"];
+
+        let forward = names.iter().fold(MvrOverrides::new(), |o, n| {
+            o.with_package(n.to_string(), "0x1".to_string())
+        });
+        let reverse = names.iter().rev().fold(MvrOverrides::new(), |o, n| {
+            o.with_package(n.to_string(), "0x1".to_string())
+        });
+
+        let json = forward.to_json().unwrap();
+        assert_eq!(json, reverse.to_json().unwrap());
+
+        let positions: Vec<usize> = ["@a/pkg", "@b/pkg", "@c/pkg", "@d/pkg"]
+            .iter()
+            .map(|n| json.find(n).unwrap())
+            .collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]));
+    }
 }
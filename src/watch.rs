@@ -0,0 +1,390 @@
+//! Polling the registry for package upgrades
+//!
+//! [`MvrResolver::watch_package`] re-resolves a package on an interval, bypassing the
+//! cache, and reports a [`PackageChange`] whenever the registry returns a new address,
+//! as it does after every upgrade. The cache entry is replaced on each poll, so
+//! resolutions pick up the new address as soon as the change is reported.
+//! [`ResolverWatcher`] does the same for a set of package and type names and
+//! broadcasts [`WatchEvent`]s to any number of subscribers.
+
+use crate::error::{MvrError, MvrResult};
+use crate::resolver::MvrResolver;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+/// Changes buffered for a slow [`PackageWatch`] consumer before polling waits
+const CHANGE_BUFFER: usize = 16;
+
+/// Events kept for slow [`ResolverWatcher`] subscribers before they lag
+const EVENT_BUFFER: usize = 64;
+
+/// New address reported for a watched package
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageChange {
+    /// Package name
+    pub name: String,
+    /// Address before the change
+    pub old_address: String,
+    /// Address the registry now returns
+    pub new_address: String,
+}
+
+/// Handle to the task started by [`MvrResolver::watch_package`]
+///
+/// Dropping the handle stops the task after its current poll.
+pub struct PackageWatch {
+    changes: mpsc::Receiver<PackageChange>,
+    shutdown: oneshot::Sender<()>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl PackageWatch {
+    /// Wait for the next change, `None` once the task has stopped
+    pub async fn next(&mut self) -> Option<PackageChange> {
+        self.changes.recv().await
+    }
+
+    /// Stop the task and wait for its current poll to finish
+    pub async fn shutdown(self) {
+        drop(self.shutdown);
+        let _ = self.task.await;
+    }
+}
+
+impl MvrResolver {
+    /// Poll the registry for address changes of `package_name` every `interval`
+    ///
+    /// The current address is resolved first, through the cache, as the baseline.
+    /// Each poll then resolves the name from the registry (see
+    /// [`MvrResolver::resolve_package_fresh`]) and reports any change on the returned
+    /// [`PackageWatch`]. Failed polls are skipped. Must be called from within a tokio
+    /// runtime.
+    pub async fn watch_package(
+        &self,
+        package_name: &str,
+        interval: Duration,
+    ) -> MvrResult<PackageWatch> {
+        if interval.is_zero() {
+            return Err(MvrError::ConfigError(
+                "watch interval must be non-zero".to_string(),
+            ));
+        }
+        let mut current = self.resolve_package(package_name).await?;
+
+        let resolver = self.clone();
+        let name = package_name.to_string();
+        let (sender, changes) = mpsc::channel(CHANGE_BUFFER);
+        let (shutdown, mut stop) = oneshot::channel::<()>();
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately
+            ticker.tick().await;
+            loop {
+                tokio::select! {
+                    _ = &mut stop => break,
+                    _ = ticker.tick() => {}
+                }
+                let address = match resolver.resolve_package_fresh(&name).await {
+                    Ok(address) => address,
+                    Err(_e) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(package = %name, error = %_e, "watch poll failed");
+                        continue;
+                    }
+                };
+                if address == current {
+                    continue;
+                }
+                let change = PackageChange {
+                    name: name.clone(),
+                    old_address: std::mem::replace(&mut current, address.clone()),
+                    new_address: address,
+                };
+                if sender.send(change).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(PackageWatch {
+            changes,
+            shutdown,
+            task,
+        })
+    }
+}
+
+/// Event broadcast by a [`ResolverWatcher`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchEvent {
+    /// The value a watched name resolves to changed
+    Changed {
+        name: String,
+        old: String,
+        new: String,
+    },
+    /// A watched name could not be re-resolved, its last value is kept
+    Failed { name: String, error: String },
+}
+
+/// Background task re-resolving a set of package and type names
+///
+/// Names containing `::` are types, others packages. Every poll resolves each name
+/// from the registry, replacing its cache entry. When a package's address changes,
+/// the cached types under that package are invalidated as well, since their
+/// signatures embed the old address. Dropping the watcher stops the task after its
+/// current poll.
+pub struct ResolverWatcher {
+    events: broadcast::Sender<WatchEvent>,
+    shutdown: oneshot::Sender<()>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl ResolverWatcher {
+    /// Resolve `names` and start re-resolving them every `interval`
+    ///
+    /// The current values are resolved first, through the cache, as the baseline,
+    /// and any failure is returned. Must be called from within a tokio runtime.
+    pub async fn spawn<I, S>(
+        resolver: &MvrResolver,
+        names: I,
+        interval: Duration,
+    ) -> MvrResult<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        if interval.is_zero() {
+            return Err(MvrError::ConfigError(
+                "watch interval must be non-zero".to_string(),
+            ));
+        }
+        let names: Vec<String> = names.into_iter().map(Into::into).collect();
+        let mut current = futures::future::try_join_all(names.iter().map(|name| async move {
+            if name.contains("::") {
+                resolver.resolve_type(name).await
+            } else {
+                resolver.resolve_package(name).await
+            }
+        }))
+        .await?;
+
+        let resolver = resolver.clone();
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        let sender = events.clone();
+        let (shutdown, mut stop) = oneshot::channel::<()>();
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately
+            ticker.tick().await;
+            loop {
+                tokio::select! {
+                    _ = &mut stop => break,
+                    _ = ticker.tick() => {}
+                }
+                let polled = futures::future::join_all(names.iter().map(|name| {
+                    let resolver = &resolver;
+                    async move {
+                        if name.contains("::") {
+                            resolver.resolve_type_fresh(name).await
+                        } else {
+                            resolver.resolve_package_fresh(name).await
+                        }
+                    }
+                }))
+                .await;
+
+                for ((name, value), result) in names.iter().zip(&mut current).zip(polled) {
+                    // Send errors only mean nobody is subscribed right now
+                    let new = match result {
+                        Ok(new) if new == *value => continue,
+                        Ok(new) => new,
+                        Err(e) => {
+                            let _ = sender.send(WatchEvent::Failed {
+                                name: name.clone(),
+                                error: e.to_string(),
+                            });
+                            continue;
+                        }
+                    };
+                    if !name.contains("::") {
+                        let _ = resolver.invalidate_prefix(&format!("{name}::"));
+                    }
+                    let _ = sender.send(WatchEvent::Changed {
+                        name: name.clone(),
+                        old: std::mem::replace(value, new.clone()),
+                        new,
+                    });
+                }
+            }
+        });
+
+        Ok(Self {
+            events,
+            shutdown,
+            task,
+        })
+    }
+
+    /// Receive the events from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<WatchEvent> {
+        self.events.subscribe()
+    }
+
+    /// Stop the task and wait for its current poll to finish
+    pub async fn shutdown(self) {
+        drop(self.shutdown);
+        let _ = self.task.await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MvrConfig;
+
+    #[tokio::test]
+    async fn test_watch_package() {
+        let mut server = mockito::Server::new_async().await;
+        let original = server
+            .mock("GET", "/v1/resolution/@suifrens/core")
+            .with_status(200)
+            .with_body(r#"{"address": "0x1"}"#)
+            .create_async()
+            .await;
+
+        let resolver = MvrResolver::new(MvrConfig::default().with_endpoint(server.url()));
+        assert!(resolver
+            .watch_package("@suifrens/core", Duration::ZERO)
+            .await
+            .is_err());
+        let mut watch = resolver
+            .watch_package("@suifrens/core", Duration::from_millis(20))
+            .await
+            .unwrap();
+
+        original.remove_async().await;
+        let _upgraded = server
+            .mock("GET", "/v1/resolution/@suifrens/core")
+            .with_status(200)
+            .with_body(r#"{"address": "0x2"}"#)
+            .create_async()
+            .await;
+
+        let change = tokio::time::timeout(Duration::from_secs(5), watch.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            change,
+            PackageChange {
+                name: "@suifrens/core".to_string(),
+                old_address: "0x1".to_string(),
+                new_address: "0x2".to_string(),
+            }
+        );
+        // The cache already serves the new address
+        assert_eq!(
+            resolver.resolve_package_cached("@suifrens/core").as_deref(),
+            Some("0x2")
+        );
+        watch.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_resolver_watcher() {
+        let mut server = mockito::Server::new_async().await;
+        let mut mocks = Vec::new();
+        for (path, body) in [
+            ("/v1/resolution/@suifrens/core", r#"{"address": "0x1"}"#),
+            (
+                "/v1/struct-definition/@suifrens/core::suifren::SuiFren",
+                r#"{"type_signature": "0x1::suifren::SuiFren"}"#,
+            ),
+            (
+                "/v1/struct-definition/@suifrens/core::capy::Capy",
+                r#"{"type_signature": "0x1::capy::Capy"}"#,
+            ),
+        ] {
+            let mock = server
+                .mock("GET", path)
+                .with_status(200)
+                .with_body(body)
+                .create_async()
+                .await;
+            mocks.push(mock);
+        }
+
+        let resolver = MvrResolver::new(MvrConfig::default().with_endpoint(server.url()));
+        resolver
+            .resolve_type("@suifrens/core::capy::Capy")
+            .await
+            .unwrap();
+        assert!(
+            ResolverWatcher::spawn(&resolver, ["@suifrens/core"], Duration::ZERO)
+                .await
+                .is_err()
+        );
+        let watcher = ResolverWatcher::spawn(
+            &resolver,
+            ["@suifrens/core", "@suifrens/core::suifren::SuiFren"],
+            Duration::from_millis(20),
+        )
+        .await
+        .unwrap();
+        let mut events = watcher.subscribe();
+
+        for mock in mocks {
+            mock.remove_async().await;
+        }
+        for (path, body) in [
+            ("/v1/resolution/@suifrens/core", r#"{"address": "0x2"}"#),
+            (
+                "/v1/struct-definition/@suifrens/core::suifren::SuiFren",
+                r#"{"type_signature": "0x2::suifren::SuiFren"}"#,
+            ),
+        ] {
+            server
+                .mock("GET", path)
+                .with_status(200)
+                .with_body(body)
+                .create_async()
+                .await;
+        }
+
+        let mut changes = Vec::new();
+        while changes.len() < 2 {
+            let event = tokio::time::timeout(Duration::from_secs(5), events.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            if let WatchEvent::Changed { name, old, new } = event {
+                changes.push((name, old, new));
+            }
+        }
+        changes.sort();
+        assert_eq!(
+            changes,
+            [
+                (
+                    "@suifrens/core".to_string(),
+                    "0x1".to_string(),
+                    "0x2".to_string()
+                ),
+                (
+                    "@suifrens/core::suifren::SuiFren".to_string(),
+                    "0x1::suifren::SuiFren".to_string(),
+                    "0x2::suifren::SuiFren".to_string()
+                ),
+            ]
+        );
+        // Types under the upgraded package are no longer served from the cache
+        assert_eq!(
+            resolver.resolve_type_cached("@suifrens/core::capy::Capy"),
+            None
+        );
+        watcher.shutdown().await;
+    }
+}
@@ -0,0 +1,128 @@
+//! Compile-time MVR name resolution for `sui-mvr`
+//!
+//! Use through `sui_mvr::mvr!` with the `static-resolution` feature rather than
+//! depending on this crate directly.
+
+use proc_macro::{TokenStream, TokenTree};
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming the mapping file, relative to the crate's manifest
+/// directory unless absolute
+const MAPPING_ENV: &str = "MVR_STATIC_OVERRIDES";
+
+/// Mapping file used when the environment variable is unset
+const DEFAULT_MAPPING: &str = "mvr.lock";
+
+/// Resolve an MVR name at build time
+///
+/// `mvr!("@suifrens/core")` expands to the address string recorded for the package,
+/// and `mvr!("@suifrens/core::suifren::SuiFren")` to the type signature, so the
+/// result can initialize a `const &str`. Names are looked up in an `mvr.lock` file
+/// (`MvrLockfile::save`) or a file in the overrides JSON format
+/// (`MvrOverrides::to_json`): `$MVR_STATIC_OVERRIDES` if set, else `mvr.lock` next
+/// to the crate's `Cargo.toml`. Unknown names fail the build, and editing the
+/// mapping file triggers a rebuild.
+#[proc_macro]
+pub fn mvr(input: TokenStream) -> TokenStream {
+    let expanded = parse_name(input).and_then(|name| {
+        let path = mapping_path()?;
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| format!("mvr!: failed to read '{}': {e}", path.display()))?;
+        let value = lookup(&json, &name)
+            .map_err(|e| format!("mvr!: {e} (mapping file '{}')", path.display()))?;
+        Ok(expand(&path, &value))
+    });
+
+    expanded
+        .unwrap_or_else(|message| format!("::core::compile_error!({message:?})"))
+        .parse()
+        .unwrap_or_default()
+}
+
+/// The single plain string literal passed to the macro
+fn parse_name(input: TokenStream) -> Result<String, String> {
+    let mut tokens = input.into_iter();
+    let literal = match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Literal(literal)), None) => literal.to_string(),
+        _ => return Err("mvr!: expected a single string literal".to_string()),
+    };
+    literal
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .filter(|name| !name.contains('\\'))
+        .map(str::to_string)
+        .ok_or_else(|| format!("mvr!: expected a plain string literal, found {literal}"))
+}
+
+fn mapping_path() -> Result<PathBuf, String> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| "mvr!: CARGO_MANIFEST_DIR is not set".to_string())?;
+    let file = std::env::var(MAPPING_ENV).unwrap_or_else(|_| DEFAULT_MAPPING.to_string());
+    Ok(Path::new(&manifest_dir).join(file))
+}
+
+/// Value recorded for `name` in the mapping file contents `json`
+fn lookup(json: &str, name: &str) -> Result<String, String> {
+    if !name.starts_with('@') {
+        return Err(format!("'{name}' is not an MVR name"));
+    }
+    let mapping: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| format!("invalid mapping file: {e}"))?;
+    let section = if name.contains("::") {
+        "types"
+    } else {
+        "packages"
+    };
+
+    // Overrides map names to strings, lockfiles to `{"value": .., "version": ..}`
+    let entry = mapping.get(section).and_then(|entries| entries.get(name));
+    entry
+        .and_then(|entry| entry.as_str().or_else(|| entry.get("value")?.as_str()))
+        .map(str::to_string)
+        .ok_or_else(|| format!("'{name}' is not in the {section} of the mapping file"))
+}
+
+/// Expression evaluating to `value` that also makes cargo track the mapping file
+fn expand(path: &Path, value: &str) -> String {
+    format!(
+        "{{ const _: &[u8] = ::core::include_bytes!({:?}); {value:?} }}",
+        path.display().to_string()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAPPING: &str = r#"{
+        "version": 1,
+        "packages": {"@suifrens/core": "0x80d7"},
+        "types": {"@suifrens/core::suifren::SuiFren": "0x80d7::suifren::SuiFren"}
+    }"#;
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(lookup(MAPPING, "@suifrens/core").unwrap(), "0x80d7");
+        assert_eq!(
+            lookup(MAPPING, "@suifrens/core::suifren::SuiFren").unwrap(),
+            "0x80d7::suifren::SuiFren"
+        );
+        assert!(lookup(MAPPING, "@suifrens/missing")
+            .unwrap_err()
+            .contains("packages"));
+        assert!(lookup(MAPPING, "suifrens").is_err());
+        assert!(lookup("not json", "@suifrens/core").is_err());
+
+        let lockfile = r#"{"format": 1, "packages": {"@a/b": {"value": "0x1", "version": "v2"}}}"#;
+        assert_eq!(lookup(lockfile, "@a/b").unwrap(), "0x1");
+    }
+
+    #[test]
+    fn test_expand() {
+        let expanded = expand(Path::new("/tmp/mvr.lock"), "0x80d7");
+        assert_eq!(
+            expanded,
+            r#"{ const _: &[u8] = ::core::include_bytes!("/tmp/mvr.lock"); "0x80d7" }"#
+        );
+    }
+}
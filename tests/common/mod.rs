@@ -55,14 +55,14 @@ pub fn create_batch_test_overrides() -> MvrOverrides {
 /// Test package names for validation testing
 pub fn invalid_package_names() -> Vec<&'static str> {
     vec![
-        "invalid-name",  // Missing @
-        "@incomplete",   // Missing /
-        "@ns/",          // Empty package name
-        "@/pkg",         // Empty namespace
-        "",              // Empty string
-        "@ns/pkg/extra", // Too many parts
-        "@",             // Just @
-        "/pkg",          // Missing @
+        "invalid-name", // Missing @
+        "@incomplete",  // Missing /
+        "@ns/",         // Empty package name
+        "@/pkg",        // Empty namespace
+        "",             // Empty string
+        "@ns/pkg//sub", // Empty subname
+        "@",            // Just @
+        "/pkg",         // Missing @
     ]
 }
 
@@ -0,0 +1,96 @@
+//! End-to-end tests against a live registry
+//!
+//! Ignored by default. Point `MVR_E2E_URL` at a registry, e.g. a local registry
+//! fixture or `https://mainnet.mvr.mystenlabs.com`, and run
+//! `cargo test --test e2e_tests -- --ignored`. `MVR_E2E_PACKAGE` names a registered
+//! package (default `@mvr/core`) and `MVR_E2E_TYPE` one of its types. With the
+//! `sui-rust-sdk` feature, `MVR_E2E_SUI_RPC_URL` and `MVR_E2E_TARGET` (a function
+//! taking no arguments) also dev-inspect a call on that fullnode.
+#![cfg(feature = "network")]
+
+use sui_mvr::prelude::*;
+
+const DEFAULT_PACKAGE: &str = "@mvr/core";
+
+/// Resolver for `MVR_E2E_URL`, `None` when the variable is not set
+fn e2e_resolver() -> Option<MvrResolver> {
+    let Ok(url) = std::env::var("MVR_E2E_URL") else {
+        eprintln!("MVR_E2E_URL is not set, skipping");
+        return None;
+    };
+    let mut config = MvrConfig::default().with_endpoint(url.trim_end_matches('/').to_string());
+    if let Ok(sui_rpc_url) = std::env::var("MVR_E2E_SUI_RPC_URL") {
+        config = config.with_sui_rpc_url(sui_rpc_url);
+    }
+    Some(MvrResolver::new(config))
+}
+
+fn e2e_package() -> String {
+    std::env::var("MVR_E2E_PACKAGE").unwrap_or_else(|_| DEFAULT_PACKAGE.to_string())
+}
+
+fn is_address(value: &str) -> bool {
+    value
+        .strip_prefix("0x")
+        .is_some_and(|hex| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+#[tokio::test]
+#[ignore = "needs a live registry, set MVR_E2E_URL"]
+async fn test_e2e_resolve_package() {
+    let Some(resolver) = e2e_resolver() else {
+        return;
+    };
+    let package = e2e_package();
+
+    let address = resolver.resolve_package(&package).await.unwrap();
+    assert!(is_address(&address), "{package} resolved to {address}");
+
+    // The bulk endpoint agrees with the single lookup
+    resolver.clear_cache().unwrap();
+    let batch = resolver.resolve_packages(&[&package]).await.unwrap();
+    assert_eq!(batch[&package], address);
+
+    let missing = resolver
+        .resolve_package("@mvr-e2e/does-not-exist")
+        .await
+        .unwrap_err();
+    assert!(missing.is_client_error(), "{missing}");
+}
+
+#[tokio::test]
+#[ignore = "needs a live registry, set MVR_E2E_URL and MVR_E2E_TYPE"]
+async fn test_e2e_resolve_type() {
+    let Some(resolver) = e2e_resolver() else {
+        return;
+    };
+    let Ok(type_name) = std::env::var("MVR_E2E_TYPE") else {
+        eprintln!("MVR_E2E_TYPE is not set, skipping");
+        return;
+    };
+
+    let signature = resolver.resolve_type(&type_name).await.unwrap();
+    let (address, path) = signature.split_once("::").unwrap();
+    assert!(is_address(address), "{type_name} resolved to {signature}");
+    assert_eq!(path.split("::").count(), 2, "{signature}");
+}
+
+#[cfg(feature = "sui-rust-sdk")]
+#[tokio::test]
+#[ignore = "needs a live registry and fullnode, set MVR_E2E_URL, MVR_E2E_SUI_RPC_URL and MVR_E2E_TARGET"]
+async fn test_e2e_dev_inspect_call() {
+    use sui_mvr::sui_sdk::Address;
+
+    let Some(resolver) = e2e_resolver() else {
+        return;
+    };
+    let Ok(target) = std::env::var("MVR_E2E_TARGET") else {
+        eprintln!("MVR_E2E_TARGET is not set, skipping");
+        return;
+    };
+
+    resolver
+        .dev_inspect_mvr_call(Address::ZERO, &target, &[], Vec::new())
+        .await
+        .unwrap();
+}
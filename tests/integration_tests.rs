@@ -205,6 +205,61 @@ async fn test_mvr_target_resolution() {
     assert!(result.is_err());
 }
 
+#[tokio::test]
+async fn test_override_coverage_for_test_names() {
+    let resolver = create_test_resolver();
+
+    let coverage = resolver.override_coverage(&[
+        "@suifrens/core",
+        "@suifrens/accessories",
+        "@suifrens/core::suifren::SuiFren",
+    ]);
+    assert!(
+        coverage.is_complete(),
+        "Uncovered: {:?}",
+        coverage.uncovered
+    );
+
+    let coverage = resolver.override_coverage(&valid_package_names());
+    assert!(coverage
+        .uncovered
+        .contains(&"@namespace/package".to_string()));
+}
+
+#[tokio::test]
+async fn test_override_coverage_normalizes_names() {
+    let resolver = MvrResolver::testnet().with_overrides(
+        MvrOverrides::new()
+            .with_package("@MyOrg/core".to_string(), "0x2".to_string())
+            .with_type(
+                "@MyOrg/core::coin::COIN".to_string(),
+                "0x2::coin::COIN".to_string(),
+            ),
+    );
+
+    let coverage =
+        resolver.override_coverage(&["@MyOrg/core", " @myorg/core ", "@MyOrg/core::coin::COIN"]);
+    assert!(
+        coverage.is_complete(),
+        "Uncovered: {:?}",
+        coverage.uncovered
+    );
+    // Coverage reports the names as the caller passed them
+    assert_eq!(coverage.covered[0], "@MyOrg/core");
+    assert_eq!(coverage.covered[1], " @myorg/core ");
+}
+
+#[tokio::test]
+async fn test_override_coverage_expands_aliases() {
+    let resolver = create_test_resolver()
+        .with_alias("core", "@suifrens/core")
+        .with_alias("missing", "@namespace/package");
+
+    let coverage = resolver.override_coverage(&["core", "core::suifren::SuiFren", "missing"]);
+    assert_eq!(coverage.covered, vec!["core", "core::suifren::SuiFren"]);
+    assert_eq!(coverage.uncovered, vec!["missing"]);
+}
+
 #[tokio::test]
 async fn test_overrides_serialization() {
     let original_overrides = create_batch_test_overrides();
@@ -260,12 +315,14 @@ async fn test_concurrent_operations() {
 #[tokio::test]
 async fn test_error_types_and_properties() {
     // Test different error types and their properties
-    let package_not_found = MvrError::PackageNotFound("test".to_string());
+    let package_not_found = MvrError::package_not_found("test".to_string());
     test_error_properties(&package_not_found, false, true);
     assert!(!package_not_found.is_rate_limited());
 
     let rate_limited = MvrError::RateLimitExceeded {
         retry_after_secs: 60,
+        request_id: None,
+        context: None,
     };
     test_error_properties(&rate_limited, true, false); // Rate limits are retryable
     assert!(rate_limited.is_rate_limited());
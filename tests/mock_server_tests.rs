@@ -0,0 +1,65 @@
+#![cfg(feature = "testing")]
+
+use sui_mvr::prelude::*;
+use sui_mvr::testing::{MockFailure, MockMvrServer};
+use tokio::time::Duration;
+
+/// Integration tests against the in-process mock registry
+///
+/// Unlike override-based tests these go through the resolver's real HTTP path,
+/// including retries, rate limiting and timeouts.
+
+#[tokio::test]
+async fn test_rate_limit_retry_over_http() {
+    let server = MockMvrServer::start().await.unwrap();
+    server.add_package("@test/pkg", "0x1");
+    server.fail_next(
+        MockFailure::RateLimited {
+            retry_after: Duration::ZERO,
+        },
+        2,
+    );
+
+    let resolver = MvrResolver::new(
+        server
+            .config()
+            .with_rate_limit_retry(2, Duration::from_secs(1)),
+    );
+    assert_eq!(resolver.resolve_package("@test/pkg").await.unwrap(), "0x1");
+    assert_eq!(server.requests(), 3);
+}
+
+#[tokio::test]
+async fn test_slow_registry_times_out() {
+    let server = MockMvrServer::start().await.unwrap();
+    server.add_package("@test/pkg", "0x1");
+    server.set_latency(Duration::from_millis(500));
+
+    let resolver = MvrResolver::new(server.config().with_timeout(Duration::from_millis(100)));
+    let err = resolver.resolve_package("@test/pkg").await.unwrap_err();
+    assert!(err.is_retryable(), "unexpected error: {err}");
+
+    server.set_latency(Duration::ZERO);
+    assert_eq!(resolver.resolve_package("@test/pkg").await.unwrap(), "0x1");
+}
+
+#[tokio::test]
+async fn test_batch_recovers_from_server_error() {
+    let server = MockMvrServer::start().await.unwrap();
+    server.add_package("@test/a", "0xa");
+    server.add_package("@test/b", "0xb");
+    server.fail_next(MockFailure::Server { status_code: 502 }, 1);
+
+    let resolver = MvrResolver::new(server.config());
+    assert!(resolver
+        .resolve_packages(&["@test/a", "@test/b"])
+        .await
+        .is_err());
+
+    let results = resolver
+        .resolve_packages(&["@test/a", "@test/b"])
+        .await
+        .unwrap();
+    assert_eq!(results["@test/a"], "0xa");
+    assert_eq!(results["@test/b"], "0xb");
+}